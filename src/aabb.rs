@@ -0,0 +1,179 @@
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// An axis-aligned bounding box.
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Aabb {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    /// The box with `a` and `b` as opposite corners, in any order.
+    pub fn from_points(a: Point, b: Point) -> Self {
+        Self::new(
+            Point::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            Point::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        )
+    }
+
+    /// Slab test: does the ray pass through the box between `t_min` and
+    /// `t_max`?
+    pub fn hit(&self, ray: &Ray, mut t_min: f64, mut t_max: f64) -> bool {
+        for axis in 0..3 {
+            let inv_d = 1.0 / ray.direction[axis];
+            let mut t0 = (self.min[axis] - ray.origin[axis]) * inv_d;
+            let mut t1 = (self.max[axis] - ray.origin[axis]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+            if t_max <= t_min {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: Point::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            max: Point::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        }
+    }
+
+    pub fn centroid(&self) -> Point {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn size(&self) -> Point {
+        self.max - self.min
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let d = self.size();
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    /// The axis (0, 1 or 2 for x, y or z) along which the box is longest.
+    pub fn longest_axis(&self) -> usize {
+        let d = self.size();
+        if d.x > d.y && d.x > d.z {
+            0
+        } else if d.y > d.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Grow any side thinner than `delta` so flat objects, such as axis
+    /// aligned rectangles, still have a box with some volume.
+    pub fn padded(&self, delta: f64) -> Aabb {
+        let mut min = self.min;
+        let mut max = self.max;
+        if max.x - min.x < delta {
+            min.x -= delta / 2.0;
+            max.x += delta / 2.0;
+        }
+        if max.y - min.y < delta {
+            min.y -= delta / 2.0;
+            max.y += delta / 2.0;
+        }
+        if max.z - min.z < delta {
+            min.z -= delta / 2.0;
+            max.z += delta / 2.0;
+        }
+        Aabb { min, max }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit() -> Aabb {
+        Aabb::new(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0))
+    }
+
+    fn ray(origin: (f64, f64, f64), direction: (f64, f64, f64)) -> Ray {
+        Ray::new(
+            Point::new(origin.0, origin.1, origin.2),
+            Point::new(direction.0, direction.1, direction.2),
+            0.0,
+        )
+    }
+
+    #[test]
+    fn slab_test() {
+        let bbox = unit();
+        let inf = f64::INFINITY;
+        // Straight through, diagonally, and from inside.
+        assert!(bbox.hit(&ray((0.5, 0.5, -2.0), (0.0, 0.0, 1.0)), 0.0, inf));
+        assert!(bbox.hit(&ray((-1.0, -1.0, -1.0), (1.0, 1.0, 1.0)), 0.0, inf));
+        assert!(bbox.hit(&ray((0.5, 0.5, 0.5), (0.0, -1.0, 0.0)), 0.0, inf));
+        // Pointing away, passing beside, and axis-parallel outside a slab.
+        assert!(!bbox.hit(&ray((0.5, 0.5, -2.0), (0.0, 0.0, -1.0)), 0.0, inf));
+        assert!(!bbox.hit(&ray((-1.0, 0.5, -2.0), (0.1, 0.0, 1.0)), 0.0, inf));
+        assert!(!bbox.hit(&ray((2.0, 0.5, -2.0), (0.0, 0.0, 1.0)), 0.0, inf));
+        // The box is entered at t = 2 and left at t = 3.
+        let r = ray((0.5, 0.5, -2.0), (0.0, 0.0, 1.0));
+        assert!(!bbox.hit(&r, 0.0, 1.5));
+        assert!(!bbox.hit(&r, 3.5, inf));
+        assert!(bbox.hit(&r, 2.5, 2.6));
+    }
+
+    #[test]
+    fn from_points_orders_corners() {
+        let bbox = Aabb::from_points(Point::new(1.0, -2.0, 3.0), Point::new(-1.0, 2.0, 0.0));
+        assert_eq!((bbox.min.x, bbox.min.y, bbox.min.z), (-1.0, -2.0, 0.0));
+        assert_eq!((bbox.max.x, bbox.max.y, bbox.max.z), (1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn union_and_measures() {
+        let other = Aabb::new(Point::new(2.0, -1.0, 0.5), Point::new(3.0, 0.5, 0.75));
+        let both = unit().union(&other);
+        assert_eq!((both.min.x, both.min.y, both.min.z), (0.0, -1.0, 0.0));
+        assert_eq!((both.max.x, both.max.y, both.max.z), (3.0, 1.0, 1.0));
+        let c = both.centroid();
+        assert_eq!((c.x, c.y, c.z), (1.5, 0.0, 0.5));
+        assert_eq!(both.longest_axis(), 0);
+        // 3 x 2 x 1
+        assert_eq!(both.surface_area(), 2.0 * (6.0 + 2.0 + 3.0));
+        assert_eq!(unit().surface_area(), 6.0);
+    }
+
+    #[test]
+    fn longest_axis() {
+        let tall = Aabb::new(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 5.0, 2.0));
+        assert_eq!(tall.longest_axis(), 1);
+        let deep = Aabb::new(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 2.0));
+        assert_eq!(deep.longest_axis(), 2);
+    }
+
+    #[test]
+    fn padding_only_grows_thin_sides() {
+        let flat = Aabb::new(Point::new(0.0, 2.0, 0.0), Point::new(1.0, 2.0, 1.0));
+        let padded = flat.padded(0.5);
+        assert_eq!((padded.min.y, padded.max.y), (1.75, 2.25));
+        assert_eq!((padded.min.x, padded.max.x), (0.0, 1.0));
+        assert_eq!((padded.min.z, padded.max.z), (0.0, 1.0));
+        let r = ray((0.5, 0.0, 0.5), (0.0, 1.0, 0.0));
+        assert!(padded.hit(&r, 0.0, f64::INFINITY));
+    }
+}
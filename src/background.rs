@@ -0,0 +1,51 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::pixel::Pixel;
+use crate::ray::Ray;
+
+/// The radiance seen by rays that leave the scene without hitting anything.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(untagged)]
+pub enum Background {
+    Solid(Pixel),
+    /// A blend from `bottom`, straight down, to `top`, straight up.
+    Gradient {
+        bottom: Pixel,
+        top: Pixel,
+    },
+}
+
+impl Default for Background {
+    /// The book's sky, white at the horizon fading to blue overhead.
+    fn default() -> Self {
+        Background::Gradient {
+            bottom: Pixel::new(1.0, 1.0, 1.0),
+            top: Pixel::new(0.4, 0.5, 1.0),
+        }
+    }
+}
+
+impl Background {
+    pub fn color(&self, ray: &Ray) -> Pixel {
+        match self {
+            Background::Solid(color) => *color,
+            Background::Gradient { bottom, top } => {
+                let t = 0.5 * (ray.direction.unit_vector().y + 1.0);
+                *bottom * (1.0 - t) + *top * t
+            }
+        }
+    }
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Background::Solid(color) => write!(f, "solid {}", color),
+            Background::Gradient { bottom, top } => {
+                write!(f, "gradient from {} to {}", bottom, top)
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::ray::Ray;
+
+/// A node in a bounding volume hierarchy. Rays that miss a node's box skip
+/// everything beneath it.
+pub struct BvhNode {
+    left: Box<dyn Hittable + Send + Sync>,
+    right: Option<Box<dyn Hittable + Send + Sync>>,
+    bbox: Aabb,
+}
+
+impl BvhNode {
+    /// Build a hierarchy by recursively splitting `objects` along the axis
+    /// where their centres are most spread out, at the point the surface area
+    /// heuristic estimates will be cheapest to trace.
+    ///
+    /// Panics if `objects` is empty or any object has no bounding box; see
+    /// `HittableList::into_bvh` for a version that handles both.
+    pub fn new(objects: Vec<Box<dyn Hittable + Send + Sync>>) -> Self {
+        let mut objects = objects
+            .into_iter()
+            .map(|o| {
+                let bbox = o
+                    .bounding_box()
+                    .expect("BVH objects must have bounding boxes");
+                (bbox, o)
+            })
+            .collect::<Vec<_>>();
+        Self::build(&mut objects)
+    }
+
+    fn build(objects: &mut Vec<(Aabb, Box<dyn Hittable + Send + Sync>)>) -> Self {
+        assert!(!objects.is_empty(), "BVH needs at least one object");
+
+        if objects.len() <= 2 {
+            let (left_box, left) = objects.remove(0);
+            return match objects.pop() {
+                Some((right_box, right)) => Self {
+                    left,
+                    right: Some(right),
+                    bbox: left_box.union(&right_box),
+                },
+                None => Self {
+                    left,
+                    right: None,
+                    bbox: left_box,
+                },
+            };
+        }
+
+        let axis = objects
+            .iter()
+            .map(|(b, _)| {
+                let c = b.centroid();
+                Aabb::new(c, c)
+            })
+            .reduce(|a, b| a.union(&b))
+            .unwrap()
+            .longest_axis();
+
+        objects.sort_by(|(a, _), (b, _)| {
+            a.centroid()[axis]
+                .partial_cmp(&b.centroid()[axis])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let mut right_objects = objects.split_off(Self::sah_split(objects));
+
+        let left = Self::build(objects);
+        let right = Self::build(&mut right_objects);
+        let bbox = left.bbox.union(&right.bbox);
+        Self {
+            left: Box::new(left),
+            right: Some(Box::new(right)),
+            bbox,
+        }
+    }
+
+    /// Pick where to split sorted `objects`, minimising the cost of each half,
+    /// estimated as its box's surface area times the number of objects in it.
+    fn sah_split(objects: &[(Aabb, Box<dyn Hittable + Send + Sync>)]) -> usize {
+        let n = objects.len();
+        let mut right_area = vec![0.0; n];
+        let mut right_box = objects[n - 1].0;
+        for i in (1..n).rev() {
+            right_box = right_box.union(&objects[i].0);
+            right_area[i] = right_box.surface_area();
+        }
+
+        let mut best = (f64::INFINITY, n / 2);
+        let mut left_box = objects[0].0;
+        for i in 1..n {
+            left_box = left_box.union(&objects[i - 1].0);
+            let cost = left_box.surface_area() * i as f64 + right_area[i] * (n - i) as f64;
+            if cost < best.0 {
+                best = (cost, i);
+            }
+        }
+        best.1
+    }
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        if !self.bbox.hit(ray, t_min, t_max) {
+            return false;
+        }
+
+        let hit_left = self.left.hit(ray, t_min, t_max, rec);
+        let t_max = if hit_left { rec.t } else { t_max };
+        let hit_right = match &self.right {
+            Some(right) => right.hit(ray, t_min, t_max, rec),
+            None => false,
+        };
+        hit_left || hit_right
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bbox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::hittable_list::HittableList;
+    use crate::material::Material;
+    use crate::pixel::Pixel;
+    use crate::point::Point;
+    use crate::sphere::Sphere;
+
+    fn sphere(x: f64, y: f64, z: f64, radius: f64) -> Box<dyn Hittable + Send + Sync> {
+        let material = Material::Lambertian {
+            albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+        };
+        Box::new(Sphere::new(Point::new(x, y, z), radius, Box::new(material)))
+    }
+
+    /// A 7x7 grid of spheres of varying size in the z = 0 plane.
+    fn grid() -> Vec<Box<dyn Hittable + Send + Sync>> {
+        (0..49)
+            .map(|i| {
+                let (x, y) = ((i % 7) as f64 - 3.0, (i / 7) as f64 - 3.0);
+                sphere(x, y, (i % 3) as f64 * 0.3, 0.1 + (i % 4) as f64 * 0.1)
+            })
+            .collect()
+    }
+
+    fn first_hit(object: &dyn Hittable, ray: &Ray) -> Option<(f64, Point)> {
+        let mut hit = HitRecord::default();
+        object
+            .hit(ray, 0.001, f64::INFINITY, &mut hit)
+            .then_some((hit.t, hit.point))
+    }
+
+    #[test]
+    fn finds_the_same_hits_as_a_list() {
+        let list = HittableList { objects: grid() };
+        let bvh = BvhNode::new(grid());
+        let mut hits = 0;
+        for i in 0..60 {
+            for j in 0..60 {
+                let target = Point::new(i as f64 / 7.5 - 4.0, j as f64 / 7.5 - 4.0, 0.0);
+                let origin = Point::new(0.5, -0.25, 10.0);
+                let ray = Ray::new(origin, target - origin, 0.0);
+                let expected = first_hit(&list, &ray);
+                let found = first_hit(&bvh, &ray);
+                assert_eq!(expected.map(|h| h.0), found.map(|h| h.0));
+                hits += expected.is_some() as usize;
+            }
+        }
+        // The grid must actually be hit for the comparison to mean anything.
+        assert!(hits > 100);
+    }
+
+    #[test]
+    fn box_encloses_every_object() {
+        let bvh = BvhNode::new(grid());
+        let bbox = bvh.bounding_box().unwrap();
+        assert_eq!((bbox.min.x, bbox.min.y), (-3.4, -3.4));
+        assert_eq!((bbox.max.x, bbox.max.y), (3.4, 3.4));
+    }
+
+    #[test]
+    fn single_object() {
+        let bvh = BvhNode::new(vec![sphere(0.0, 0.0, 0.0, 1.0)]);
+        let ray = Ray::new(Point::new(0.0, 0.0, 5.0), Point::new(0.0, 0.0, -1.0), 0.0);
+        assert_eq!(first_hit(&bvh, &ray).map(|h| h.0), Some(4.0));
+    }
+
+    #[test]
+    fn sah_splits_off_a_distant_outlier() {
+        // Splitting in the middle would give one half a box spanning the
+        // whole scene; isolating the far sphere is much cheaper.
+        let mut objects = (0..8)
+            .map(|i| sphere(i as f64 * 0.1, 0.0, 0.0, 0.05))
+            .chain(std::iter::once(sphere(100.0, 0.0, 0.0, 0.05)))
+            .map(|o| (o.bounding_box().unwrap(), o))
+            .collect::<Vec<_>>();
+        objects.sort_by(|(a, _), (b, _)| a.min.x.partial_cmp(&b.min.x).unwrap());
+        assert_eq!(BvhNode::sah_split(&objects), 8);
+    }
+}
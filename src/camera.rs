@@ -1,3 +1,5 @@
+use rand::Rng;
+
 use crate::point::Point;
 use crate::ray::Ray;
 
@@ -11,6 +13,10 @@ pub struct Camera {
     pub u: Point,
     pub v: Point,
     pub w: Point,
+    /// When the shutter opens and closes. Rays are cast at random times in
+    /// between.
+    pub time0: f64,
+    pub time1: f64,
 }
 
 impl Camera {
@@ -35,7 +41,7 @@ impl Camera {
 
         let origin = lookfrom;
         let horizontal = u * viewport_width * focus_dist;
-        let vertical = v * viewport_width * focus_dist;
+        let vertical = v * viewport_height * focus_dist;
         let lower_left_corner = origin - horizontal / 2.0 - vertical / 2.0 - w * focus_dist;
 
         let lens_radius = aperture / 2.0;
@@ -49,6 +55,16 @@ impl Camera {
             w,
             u,
             v,
+            time0: 0.0,
+            time1: 0.0,
+        }
+    }
+
+    pub fn with_shutter(self, time0: f64, time1: f64) -> Self {
+        Camera {
+            time0,
+            time1,
+            ..self
         }
     }
 
@@ -56,11 +72,19 @@ impl Camera {
         let rd = Point::random_in_unit_disk() * self.lens_radius;
         let offset = self.u * rd.x + self.v * rd.y;
 
+        let time = if self.time1 > self.time0 {
+            rand::thread_rng().gen_range(self.time0, self.time1)
+        } else {
+            self.time0
+        };
+
         Ray {
             origin: self.origin + offset,
             direction: self.lower_left_corner + self.horizontal * s + self.vertical * t
                 - self.origin
                 - offset,
+            time,
+            wavelength: None,
         }
     }
 }
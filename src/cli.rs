@@ -0,0 +1,241 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use raytracing_in_one_weekend::point::Point;
+use raytracing_in_one_weekend::scene::Scene;
+use raytracing_in_one_weekend::tonemap::ToneMapper;
+
+#[derive(Parser)]
+#[command(about = "A ray tracer following Ray Tracing in One Weekend")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Render a scene and save it to disk
+    Render {
+        #[command(flatten)]
+        settings: RenderArgs,
+        /// Where to save the rendered image; `.ppm` files are written
+        /// directly and `-` writes a PPM to stdout
+        #[arg(short, long, default_value = "out.png")]
+        output: PathBuf,
+        /// Save the image as soon as it is rendered instead of displaying it
+        /// first; always on when built without the `window` feature
+        #[arg(long)]
+        headless: bool,
+    },
+    /// Quickly render a scene at reduced quality
+    Preview {
+        #[command(flatten)]
+        settings: RenderArgs,
+        /// Save the preview here as well as displaying it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Don't display the preview, only save it to --output; always on
+        /// when built without the `window` feature
+        #[arg(long)]
+        headless: bool,
+    },
+    /// Print a scene's settings without rendering it
+    Info {
+        #[command(flatten)]
+        settings: RenderArgs,
+    },
+    /// Time tracing one ray per pixel with and without the bounding volume
+    /// hierarchy
+    Bench {
+        #[command(flatten)]
+        settings: RenderArgs,
+    },
+}
+
+#[derive(Args)]
+pub struct RenderArgs {
+    /// TOML scene file; the random cover scene is used if omitted
+    pub scene: Option<PathBuf>,
+
+    /// Image width in pixels
+    #[arg(long, value_parser = positive_usize)]
+    pub width: Option<usize>,
+    /// Image width divided by height
+    #[arg(long, value_parser = positive_f64, allow_hyphen_values = true)]
+    pub aspect_ratio: Option<f64>,
+    /// Rays traced through each pixel
+    #[arg(short, long, value_parser = positive_usize)]
+    pub samples_per_pixel: Option<usize>,
+    /// Maximum number of bounces per ray
+    #[arg(long, value_parser = positive_usize)]
+    pub max_depth: Option<usize>,
+    /// Curve used to convert the image for display: gamma2, clamp, srgb,
+    /// reinhard or aces
+    #[arg(long)]
+    pub tone_map: Option<ToneMapper>,
+    /// Exposure adjustment in stops, applied before tone mapping
+    #[arg(long, value_parser = finite_f64, allow_hyphen_values = true)]
+    pub exposure: Option<f64>,
+    /// Trace one wavelength per ray instead of RGB, showing dispersion
+    #[arg(long)]
+    pub spectral: bool,
+
+    /// Camera position, as x,y,z
+    #[arg(long, value_parser = point, allow_hyphen_values = true)]
+    pub lookfrom: Option<Point>,
+    /// Point the camera looks at, as x,y,z
+    #[arg(long, value_parser = point, allow_hyphen_values = true)]
+    pub lookat: Option<Point>,
+    /// Camera up direction, as x,y,z
+    #[arg(long, value_parser = point, allow_hyphen_values = true)]
+    pub vup: Option<Point>,
+    /// Vertical field of view in degrees
+    #[arg(long, value_parser = positive_f64, allow_hyphen_values = true)]
+    pub vfov: Option<f64>,
+    /// Lens diameter; 0 disables depth of field
+    #[arg(long, value_parser = non_negative_f64, allow_hyphen_values = true)]
+    pub aperture: Option<f64>,
+    /// Distance to the plane of perfect focus
+    #[arg(long, value_parser = positive_f64, allow_hyphen_values = true)]
+    pub focus_dist: Option<f64>,
+    /// Time the shutter opens, for motion blur
+    #[arg(long, value_parser = finite_f64, allow_hyphen_values = true)]
+    pub shutter_open: Option<f64>,
+    /// Time the shutter closes
+    #[arg(long, value_parser = finite_f64, allow_hyphen_values = true)]
+    pub shutter_close: Option<f64>,
+}
+
+impl RenderArgs {
+    /// Replace the scene's settings with any that were given on the command
+    /// line.
+    pub fn apply(&self, scene: &mut Scene) {
+        let image = &mut scene.image;
+        image.width = self.width.unwrap_or(image.width);
+        image.aspect_ratio = self.aspect_ratio.unwrap_or(image.aspect_ratio);
+        image.samples_per_pixel = self.samples_per_pixel.unwrap_or(image.samples_per_pixel);
+        image.max_depth = self.max_depth.unwrap_or(image.max_depth);
+        image.tone_map = self.tone_map.unwrap_or(image.tone_map);
+        image.exposure = self.exposure.unwrap_or(image.exposure);
+        image.spectral |= self.spectral;
+
+        let camera = &mut scene.camera;
+        camera.lookfrom = self.lookfrom.unwrap_or(camera.lookfrom);
+        camera.lookat = self.lookat.unwrap_or(camera.lookat);
+        camera.vup = self.vup.unwrap_or(camera.vup);
+        camera.vfov = self.vfov.unwrap_or(camera.vfov);
+        camera.aperture = self.aperture.unwrap_or(camera.aperture);
+        camera.focus_dist = self.focus_dist.unwrap_or(camera.focus_dist);
+        camera.shutter_open = self.shutter_open.unwrap_or(camera.shutter_open);
+        camera.shutter_close = self.shutter_close.unwrap_or(camera.shutter_close);
+    }
+}
+
+fn positive_usize(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn finite_f64(s: &str) -> Result<f64, String> {
+    let n = s.parse::<f64>().map_err(|e| e.to_string())?;
+    if !n.is_finite() {
+        return Err("must be a finite number".to_string());
+    }
+    Ok(n)
+}
+
+fn non_negative_f64(s: &str) -> Result<f64, String> {
+    let n = finite_f64(s)?;
+    if n < 0.0 {
+        return Err("must be a finite number no less than 0".to_string());
+    }
+    Ok(n)
+}
+
+fn positive_f64(s: &str) -> Result<f64, String> {
+    let n = non_negative_f64(s)?;
+    if n == 0.0 {
+        return Err("must be greater than 0".to_string());
+    }
+    Ok(n)
+}
+
+fn point(s: &str) -> Result<Point, String> {
+    let parts = s
+        .split(',')
+        .map(|c| finite_f64(c.trim()))
+        .collect::<Result<Vec<f64>, String>>()?;
+    match parts[..] {
+        [x, y, z] => Ok(Point::new(x, y, z)),
+        _ => Err("expected three comma separated numbers, e.g. 13,2,3".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use raytracing_in_one_weekend::hittable_list::HittableList;
+
+    fn settings(args: &[&str]) -> RenderArgs {
+        let args = ["raytracer", "info"].iter().chain(args);
+        match Cli::try_parse_from(args).unwrap().command {
+            Command::Info { settings } => settings,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn apply_overrides_only_given_settings() {
+        let mut scene = Scene::new(HittableList::default());
+        let defaults = (scene.image, scene.camera.vfov);
+        settings(&[
+            "--width",
+            "64",
+            "--lookfrom",
+            "1, -2,3",
+            "--tone-map",
+            "reinhard",
+            "--spectral",
+        ])
+        .apply(&mut scene);
+
+        assert_eq!(scene.image.width, 64);
+        assert_eq!(scene.image.tone_map, ToneMapper::Reinhard);
+        assert!(scene.image.spectral);
+        let from = scene.camera.lookfrom;
+        assert_eq!((from.x, from.y, from.z), (1.0, -2.0, 3.0));
+        assert_eq!(scene.image.aspect_ratio, defaults.0.aspect_ratio);
+        assert_eq!(scene.image.samples_per_pixel, defaults.0.samples_per_pixel);
+        assert_eq!(scene.camera.vfov, defaults.1);
+    }
+
+    #[test]
+    fn no_flags_leave_the_scene_alone() {
+        let mut scene = Scene::new(HittableList::default());
+        scene.image.spectral = true;
+        scene.image.width = 17;
+        settings(&[]).apply(&mut scene);
+        assert!(scene.image.spectral);
+        assert_eq!(scene.image.width, 17);
+    }
+
+    #[test]
+    fn rejects_bad_values() {
+        assert!(point("nan,0,0").is_err());
+        assert!(point("1,2").is_err());
+        assert!(point("1,2,3,4").is_err());
+        assert!(positive_usize("0").is_err());
+        assert!(positive_f64("0").is_err());
+        assert!(non_negative_f64("-1").is_err());
+        assert!(finite_f64("inf").is_err());
+        for args in [["--width", "0"], ["--aperture", "-1"], ["--vup", "0,inf,0"]] {
+            let args = ["raytracer", "info"].iter().chain(&args);
+            assert!(Cli::try_parse_from(args).is_err());
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::aabb::Aabb;
+use crate::disk;
+use crate::frame::{self, Frame};
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// A cone with a base of `radius` at `base` narrowing to a point at `apex`.
+/// Without a cap the base is open.
+pub struct Cone {
+    pub base: Point,
+    pub apex: Point,
+    pub radius: f64,
+    pub capped: bool,
+    pub material: Box<Material>,
+    frame: Frame,
+    height: f64,
+}
+
+impl Cone {
+    pub fn new(
+        base: Point,
+        apex: Point,
+        radius: f64,
+        capped: bool,
+        material: Box<Material>,
+    ) -> Self {
+        Cone {
+            base,
+            apex,
+            radius,
+            capped,
+            material,
+            frame: Frame::new(base, apex - base),
+            height: (apex - base).length(),
+        }
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, t_min: f64, mut t_max: f64, hit: &mut HitRecord) -> bool {
+        let local = self.frame.ray_to_local(ray);
+        let (o, d) = (local.origin, local.direction);
+        let mut found = None;
+
+        // The side is x² + z² = (r - ky)² for 0 <= y <= h, with k = r / h.
+        let k = self.radius / self.height;
+        let s = self.radius - k * o.y;
+        let a = d.x * d.x + d.z * d.z - k * k * d.y * d.y;
+        let half_b = o.x * d.x + o.z * d.z + k * d.y * s;
+        let c = o.x * o.x + o.z * o.z - s * s;
+        let roots = if a.abs() < 1e-12 {
+            // The ray is parallel to the side, so there's at most one root.
+            if half_b.abs() < 1e-12 {
+                Vec::new()
+            } else {
+                vec![-c / (2.0 * half_b)]
+            }
+        } else {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant < 0.0 {
+                Vec::new()
+            } else {
+                let root = discriminant.sqrt();
+                let (t0, t1) = ((-half_b - root) / a, (-half_b + root) / a);
+                vec![t0.min(t1), t0.max(t1)]
+            }
+        };
+        for t in roots {
+            let p = local.at(t);
+            if t > t_min && t < t_max && p.y >= 0.0 && p.y <= self.height {
+                // The apex has no single normal, so it faces along the axis.
+                let radial = Point::new(p.x, k * (self.radius - k * p.y), p.z);
+                let normal = if radial.length_squared() > 0.0 {
+                    radial.unit_vector()
+                } else {
+                    Point::new(0.0, 1.0, 0.0)
+                };
+                found = Some((t, normal, (frame::azimuth(p), p.y / self.height)));
+                t_max = t;
+                break;
+            }
+        }
+
+        if self.capped {
+            if let Some(t) = disk::intersect(&local, 0.0, self.radius, t_min, t_max) {
+                let p = local.at(t);
+                let v = (p.x * p.x + p.z * p.z).sqrt() / self.radius;
+                found = Some((t, Point::new(0.0, -1.0, 0.0), (frame::azimuth(p), v)));
+            }
+        }
+
+        match found {
+            Some((t, normal, uv)) => {
+                frame::set_hit(hit, ray, &self.frame, t, normal, uv);
+                hit.material = self.material.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let base = disk::bounding_box(self.base, self.frame.y, self.radius);
+        Some(base.union(&Aabb::new(self.apex, self.apex)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::Pixel;
+
+    fn cone() -> Cone {
+        let material = Box::new(Material::Lambertian {
+            albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+        });
+        Cone::new(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(0.0, 2.0, 0.0),
+            1.0,
+            true,
+            material,
+        )
+    }
+
+    fn trace(cone: &Cone, origin: Point, direction: Point) -> Option<HitRecord> {
+        let mut hit = HitRecord::default();
+        let ray = Ray::new(origin, direction, 0.0);
+        cone.hit(&ray, 0.001, f64::INFINITY, &mut hit)
+            .then_some(hit)
+    }
+
+    fn assert_point_close(a: Point, b: Point) {
+        assert!((a - b).length() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn apex_faces_along_the_axis() {
+        let hit = trace(
+            &cone(),
+            Point::new(0.0, 5.0, 0.0),
+            Point::new(0.0, -1.0, 0.0),
+        )
+        .unwrap();
+        assert!((hit.t - 3.0).abs() < 1e-9);
+        assert_point_close(hit.normal, Point::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn side_normal_leans_towards_the_apex() {
+        let hit = trace(
+            &cone(),
+            Point::new(5.0, 1.0, 0.0),
+            Point::new(-1.0, 0.0, 0.0),
+        )
+        .unwrap();
+        assert!((hit.t - 4.5).abs() < 1e-9);
+        assert_point_close(hit.normal, Point::new(2.0, 1.0, 0.0).unit_vector());
+    }
+
+    #[test]
+    fn cap_closes_the_base() {
+        let hit = trace(
+            &cone(),
+            Point::new(0.5, -3.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        )
+        .unwrap();
+        assert!((hit.t - 3.0).abs() < 1e-9);
+        assert_point_close(hit.normal, Point::new(0.0, -1.0, 0.0));
+    }
+}
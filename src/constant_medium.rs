@@ -0,0 +1,61 @@
+use rand::Rng;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// A volume of smoke or fog of uniform `density` filling a closed
+/// `boundary`. Rays travelling through it scatter at random with an
+/// exponential distribution of distances, off the `phase` material, which is
+/// normally `Material::Isotropic`.
+pub struct ConstantMedium {
+    pub boundary: Box<dyn Hittable + Send + Sync>,
+    pub density: f64,
+    pub phase: Box<Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(
+        boundary: Box<dyn Hittable + Send + Sync>,
+        density: f64,
+        phase: Box<Material>,
+    ) -> Self {
+        ConstantMedium {
+            boundary,
+            density,
+            phase,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        let speed = ray.direction.length();
+        let mut rng = rand::thread_rng();
+        for span in self.boundary.spans(ray, t_min, t_max) {
+            let length = (span.exit.t - span.enter.t) * speed;
+            // Distances to scatter are memoryless, so each span can be
+            // sampled on its own.
+            let distance = -rng.gen_range(0.0_f64, 1.0).ln() / self.density;
+            if distance < length {
+                hit.t = span.enter.t + distance / speed;
+                hit.point = ray.at(hit.t);
+                // A scattering event has no surface; the normal is arbitrary.
+                hit.normal = Point::new(1.0, 0.0, 0.0);
+                hit.front_face = true;
+                hit.u = 0.0;
+                hit.v = 0.0;
+                hit.material = self.phase.clone();
+                return true;
+            }
+        }
+        false
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
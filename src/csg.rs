@@ -0,0 +1,206 @@
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::{Hittable, Span};
+use crate::ray::Ray;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsgOp {
+    Union,
+    Intersection,
+    /// The left solid with the right one cut away.
+    Difference,
+}
+
+impl CsgOp {
+    fn inside(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOp::Union => in_left || in_right,
+            CsgOp::Intersection => in_left && in_right,
+            CsgOp::Difference => in_left && !in_right,
+        }
+    }
+}
+
+/// A boolean combination of two closed solids. Surfaces keep the material
+/// of the solid they come from, so a cut shows the material of the cutter.
+pub struct Csg {
+    pub op: CsgOp,
+    pub left: Box<dyn Hittable + Send + Sync>,
+    pub right: Box<dyn Hittable + Send + Sync>,
+}
+
+impl Csg {
+    pub fn new(
+        op: CsgOp,
+        left: Box<dyn Hittable + Send + Sync>,
+        right: Box<dyn Hittable + Send + Sync>,
+    ) -> Self {
+        Csg { op, left, right }
+    }
+}
+
+impl Hittable for Csg {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        let first = self
+            .spans(ray, t_min, t_max)
+            .into_iter()
+            .flat_map(|span| vec![span.enter, span.exit])
+            .find(|rec| rec.t > t_min && rec.t < t_max);
+        match first {
+            Some(rec) => {
+                *hit = rec;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        match self.op {
+            CsgOp::Union => Some(self.left.bounding_box()?.union(&self.right.bounding_box()?)),
+            CsgOp::Intersection => self
+                .left
+                .bounding_box()
+                .or_else(|| self.right.bounding_box()),
+            CsgOp::Difference => self.left.bounding_box(),
+        }
+    }
+
+    /// Merge the spans of both solids, keeping the parts the operation
+    /// selects.
+    fn spans(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<Span> {
+        let mut events = Vec::new();
+        for (from_left, spans) in [
+            (true, self.left.spans(ray, t_min, t_max)),
+            (false, self.right.spans(ray, t_min, t_max)),
+        ] {
+            for span in spans {
+                events.push((span.enter, from_left, true));
+                events.push((span.exit, from_left, false));
+            }
+        }
+        events.sort_by(|a, b| a.0.t.total_cmp(&b.0.t));
+
+        let (mut in_left, mut in_right) = (false, false);
+        let mut enter = None;
+        let mut spans = Vec::new();
+        for (mut rec, from_left, entering) in events {
+            let was_inside = self.op.inside(in_left, in_right);
+            if from_left {
+                in_left = entering;
+            } else {
+                in_right = entering;
+            }
+            let inside = self.op.inside(in_left, in_right);
+            // Hit normals always face the ray, so only the side changes.
+            if inside && !was_inside {
+                rec.front_face = true;
+                enter = Some(rec);
+            } else if !inside && was_inside {
+                rec.front_face = false;
+                if let Some(enter) = enter.take() {
+                    spans.push(Span { enter, exit: rec });
+                }
+            }
+        }
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::material::Material;
+    use crate::pixel::Pixel;
+    use crate::point::Point;
+    use crate::sphere::Sphere;
+
+    fn sphere(x: f64, radius: f64) -> Box<dyn Hittable + Send + Sync> {
+        let material = Material::Lambertian {
+            albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+        };
+        Box::new(Sphere::new(
+            Point::new(x, 0.0, 0.0),
+            radius,
+            Box::new(material),
+        ))
+    }
+
+    /// The (enter, exit) distances of each span along a ray down the x axis
+    /// from x = -5.
+    fn spans(csg: &Csg) -> Vec<(f64, f64)> {
+        let ray = Ray::new(Point::new(-5.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0), 0.0);
+        csg.spans(&ray, 0.001, f64::INFINITY)
+            .iter()
+            .map(|s| (s.enter.t, s.exit.t))
+            .collect()
+    }
+
+    fn assert_spans(csg: &Csg, expected: &[(f64, f64)]) {
+        let found = spans(csg);
+        assert_eq!(found.len(), expected.len(), "{:?}", found);
+        for (a, b) in found.iter().zip(expected) {
+            assert!(
+                (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9,
+                "{:?}",
+                found
+            );
+        }
+    }
+
+    /// Spheres covering x in [-1, 1] and [0, 2].
+    fn overlapping(op: CsgOp) -> Csg {
+        Csg::new(op, sphere(0.0, 1.0), sphere(1.0, 1.0))
+    }
+
+    #[test]
+    fn operations_on_overlapping_spheres() {
+        assert_spans(&overlapping(CsgOp::Union), &[(4.0, 7.0)]);
+        assert_spans(&overlapping(CsgOp::Intersection), &[(5.0, 6.0)]);
+        assert_spans(&overlapping(CsgOp::Difference), &[(4.0, 5.0)]);
+        // Difference isn't symmetric.
+        let reversed = Csg::new(CsgOp::Difference, sphere(1.0, 1.0), sphere(0.0, 1.0));
+        assert_spans(&reversed, &[(6.0, 7.0)]);
+    }
+
+    #[test]
+    fn difference_can_split_a_span() {
+        let hollowed = Csg::new(CsgOp::Difference, sphere(0.0, 2.0), sphere(0.0, 0.5));
+        assert_spans(&hollowed, &[(3.0, 4.5), (5.5, 7.0)]);
+        let missed = Csg::new(CsgOp::Difference, sphere(0.0, 1.0), sphere(5.0, 1.0));
+        assert_spans(&missed, &[(4.0, 6.0)]);
+        let swallowed = Csg::new(CsgOp::Difference, sphere(0.0, 0.5), sphere(0.0, 2.0));
+        assert_spans(&swallowed, &[]);
+    }
+
+    #[test]
+    fn disjoint_solids() {
+        let apart = |op| Csg::new(op, sphere(0.0, 1.0), sphere(3.0, 1.0));
+        assert_spans(&apart(CsgOp::Union), &[(4.0, 6.0), (7.0, 9.0)]);
+        assert_spans(&apart(CsgOp::Intersection), &[]);
+    }
+
+    #[test]
+    fn cut_surfaces_face_the_ray() {
+        let hollowed = Csg::new(CsgOp::Difference, sphere(0.0, 2.0), sphere(0.0, 0.5));
+        // From the middle of the hole, the first surface is the cutter's,
+        // entered from outside the solid.
+        let ray = Ray::new(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0), 0.0);
+        let mut hit = HitRecord::default();
+        assert!(hollowed.hit(&ray, 0.001, f64::INFINITY, &mut hit));
+        assert!((hit.t - 0.5).abs() < 1e-9);
+        assert!(hit.front_face);
+        assert!((hit.normal - Point::new(-1.0, 0.0, 0.0)).length() < 1e-9);
+    }
+
+    #[test]
+    fn bounding_boxes() {
+        let bounds = |op| overlapping(op).bounding_box().unwrap();
+        assert_eq!(
+            (bounds(CsgOp::Union).min.x, bounds(CsgOp::Union).max.x),
+            (-1.0, 2.0)
+        );
+        assert_eq!(bounds(CsgOp::Difference).max.x, 1.0);
+    }
+}
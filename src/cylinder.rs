@@ -0,0 +1,159 @@
+use crate::aabb::Aabb;
+use crate::disk;
+use crate::frame::{self, Frame};
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// A cylinder of `radius` around the segment from `base` to `top`. Without
+/// caps it is an open tube that can be seen into.
+pub struct Cylinder {
+    pub base: Point,
+    pub top: Point,
+    pub radius: f64,
+    pub capped: bool,
+    pub material: Box<Material>,
+    frame: Frame,
+    height: f64,
+}
+
+impl Cylinder {
+    pub fn new(
+        base: Point,
+        top: Point,
+        radius: f64,
+        capped: bool,
+        material: Box<Material>,
+    ) -> Self {
+        Cylinder {
+            base,
+            top,
+            radius,
+            capped,
+            material,
+            frame: Frame::new(base, top - base),
+            height: (top - base).length(),
+        }
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, t_min: f64, mut t_max: f64, hit: &mut HitRecord) -> bool {
+        let local = self.frame.ray_to_local(ray);
+        let (o, d) = (local.origin, local.direction);
+        let mut found = None;
+
+        let a = d.x * d.x + d.z * d.z;
+        let half_b = o.x * d.x + o.z * d.z;
+        let c = o.x * o.x + o.z * o.z - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if a > 1e-12 && discriminant >= 0.0 {
+            let root = discriminant.sqrt();
+            for &t in &[(-half_b - root) / a, (-half_b + root) / a] {
+                let p = local.at(t);
+                if t > t_min && t < t_max && p.y >= 0.0 && p.y <= self.height {
+                    let normal = Point::new(p.x, 0.0, p.z) / self.radius;
+                    found = Some((t, normal, (frame::azimuth(p), p.y / self.height)));
+                    t_max = t;
+                    break;
+                }
+            }
+        }
+
+        if self.capped {
+            for &(y, normal_y) in &[(0.0, -1.0), (self.height, 1.0)] {
+                if let Some(t) = disk::intersect(&local, y, self.radius, t_min, t_max) {
+                    let p = local.at(t);
+                    let v = (p.x * p.x + p.z * p.z).sqrt() / self.radius;
+                    let normal = Point::new(0.0, normal_y, 0.0);
+                    found = Some((t, normal, (frame::azimuth(p), v)));
+                    t_max = t;
+                }
+            }
+        }
+
+        match found {
+            Some((t, normal, uv)) => {
+                frame::set_hit(hit, ray, &self.frame, t, normal, uv);
+                hit.material = self.material.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let axis = self.frame.y;
+        let base = disk::bounding_box(self.base, axis, self.radius);
+        let top = disk::bounding_box(self.top, axis, self.radius);
+        Some(base.union(&top))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::Pixel;
+
+    fn cylinder(capped: bool) -> Cylinder {
+        let material = Box::new(Material::Lambertian {
+            albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+        });
+        Cylinder::new(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(0.0, 2.0, 0.0),
+            1.0,
+            capped,
+            material,
+        )
+    }
+
+    fn trace(cylinder: &Cylinder, origin: Point, direction: Point) -> Option<HitRecord> {
+        let mut hit = HitRecord::default();
+        let ray = Ray::new(origin, direction, 0.0);
+        cylinder
+            .hit(&ray, 0.001, f64::INFINITY, &mut hit)
+            .then_some(hit)
+    }
+
+    fn assert_point_close(a: Point, b: Point) {
+        assert!((a - b).length() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn tube_hit() {
+        let c = cylinder(true);
+        let hit = trace(&c, Point::new(5.0, 1.0, 0.0), Point::new(-1.0, 0.0, 0.0)).unwrap();
+        assert!((hit.t - 4.0).abs() < 1e-9);
+        assert!(hit.front_face);
+        assert_point_close(hit.normal, Point::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn cap_hit() {
+        let c = cylinder(true);
+        let hit = trace(&c, Point::new(0.5, 5.0, 0.0), Point::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((hit.t - 3.0).abs() < 1e-9);
+        assert_point_close(hit.normal, Point::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn hit_from_inside_faces_back() {
+        let c = cylinder(true);
+        let hit = trace(&c, Point::new(0.0, 1.0, 0.0), Point::new(1.0, 0.0, 0.0)).unwrap();
+        assert!((hit.t - 1.0).abs() < 1e-9);
+        assert!(!hit.front_face);
+        assert_point_close(hit.normal, Point::new(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn open_tube_can_be_seen_into() {
+        let c = cylinder(false);
+        assert!(trace(&c, Point::new(0.0, 5.0, 0.0), Point::new(0.0, -1.0, 0.0)).is_none());
+        let hit = trace(&c, Point::new(0.0, 5.0, 0.0), Point::new(0.5, -2.0, 0.0)).unwrap();
+        assert!(!hit.front_face);
+        assert_point_close(hit.point, Point::new(1.0, 1.0, 0.0));
+    }
+}
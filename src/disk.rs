@@ -0,0 +1,113 @@
+use crate::aabb::Aabb;
+use crate::frame::{self, Frame};
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// A flat disk at `center` facing along `normal`.
+pub struct Disk {
+    pub center: Point,
+    pub normal: Point,
+    pub radius: f64,
+    pub material: Box<Material>,
+    frame: Frame,
+}
+
+impl Disk {
+    pub fn new(center: Point, normal: Point, radius: f64, material: Box<Material>) -> Self {
+        Disk {
+            center,
+            normal: normal.unit_vector(),
+            radius,
+            material,
+            frame: Frame::new(center, normal),
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        let local = self.frame.ray_to_local(ray);
+        match intersect(&local, 0.0, self.radius, t_min, t_max) {
+            Some(t) => {
+                let p = local.at(t);
+                let uv = (frame::azimuth(p), p.length() / self.radius);
+                frame::set_hit(hit, ray, &self.frame, t, Point::new(0.0, 1.0, 0.0), uv);
+                hit.material = self.material.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(bounding_box(self.center, self.normal, self.radius))
+    }
+}
+
+/// Intersect a ray in local coordinates with the disk of `radius` around the
+/// y axis in the plane y = `height`.
+pub fn intersect(ray: &Ray, height: f64, radius: f64, t_min: f64, t_max: f64) -> Option<f64> {
+    let t = (height - ray.origin.y) / ray.direction.y;
+    // A ray parallel to the disk gives an infinite or NaN `t`.
+    if !(t > t_min && t < t_max) {
+        return None;
+    }
+    let p = ray.at(t);
+    if p.x * p.x + p.z * p.z > radius * radius {
+        return None;
+    }
+    Some(t)
+}
+
+/// The bounds of a disk of `radius` at `center` facing along unit `normal`.
+pub fn bounding_box(center: Point, normal: Point, radius: f64) -> Aabb {
+    let extent = |n: f64| radius * (1.0 - n * n).max(0.0).sqrt();
+    let e = Point::new(extent(normal.x), extent(normal.y), extent(normal.z));
+    Aabb::new(center - e, center + e).padded(1e-4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::Pixel;
+
+    fn disk() -> Disk {
+        let material = Box::new(Material::Lambertian {
+            albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+        });
+        Disk::new(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            1.0,
+            material,
+        )
+    }
+
+    fn trace(disk: &Disk, origin: Point, direction: Point) -> Option<HitRecord> {
+        let mut hit = HitRecord::default();
+        let ray = Ray::new(origin, direction, 0.0);
+        disk.hit(&ray, 0.001, f64::INFINITY, &mut hit)
+            .then_some(hit)
+    }
+
+    #[test]
+    fn tilted_disk_hit_and_miss() {
+        let d = disk();
+        let hit = trace(&d, Point::new(3.0, 3.0, 0.5), Point::new(-1.0, -1.0, 0.0)).unwrap();
+        assert!((hit.t - 3.0).abs() < 1e-9);
+        assert!(hit.front_face);
+        assert!((hit.normal - Point::new(1.0, 1.0, 0.0).unit_vector()).length() < 1e-9);
+        assert!(trace(&d, Point::new(3.0, 3.0, 1.5), Point::new(-1.0, -1.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn bounding_box_of_tilted_disk() {
+        let b = disk().bounding_box().unwrap();
+        let e = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((b.max.x - e).abs() < 1e-3 && (b.max.y - e).abs() < 1e-3);
+        assert!((b.max.z - 1.0).abs() < 1e-3);
+    }
+}
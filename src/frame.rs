@@ -0,0 +1,74 @@
+use std::f64::consts::PI;
+
+use crate::hittable::HitRecord;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// An orthonormal coordinate frame at `origin` whose local y axis points
+/// along a given direction. Round primitives are intersected in their frame,
+/// where their axis is y.
+#[derive(Clone, Copy, Debug)]
+pub struct Frame {
+    pub origin: Point,
+    pub x: Point,
+    pub y: Point,
+    pub z: Point,
+}
+
+impl Frame {
+    pub fn new(origin: Point, up: Point) -> Self {
+        let y = up.unit_vector();
+        let other = if y.x.abs() > 0.9 {
+            Point::new(0.0, 0.0, 1.0)
+        } else {
+            Point::new(1.0, 0.0, 0.0)
+        };
+        let z = other.cross(&y).unit_vector();
+        let x = y.cross(&z);
+        Frame { origin, x, y, z }
+    }
+
+    pub fn to_local(&self, p: Point) -> Point {
+        self.vector_to_local(p - self.origin)
+    }
+
+    pub fn vector_to_local(&self, v: Point) -> Point {
+        Point::new(v.dot(&self.x), v.dot(&self.y), v.dot(&self.z))
+    }
+
+    pub fn vector_to_world(&self, v: Point) -> Point {
+        self.x * v.x + self.y * v.y + self.z * v.z
+    }
+
+    /// The ray in local coordinates. The frame has no scale, so ray
+    /// parameters are the same in both.
+    pub fn ray_to_local(&self, ray: &Ray) -> Ray {
+        Ray::new(
+            self.to_local(ray.origin),
+            self.vector_to_local(ray.direction),
+            ray.time,
+        )
+    }
+}
+
+/// The angle of a local point around the y axis, mapped onto [0, 1].
+pub fn azimuth(p: Point) -> f64 {
+    (p.z.atan2(p.x) + PI) / (2.0 * PI)
+}
+
+/// Fill in everything but the material for a hit at `t` whose outward normal
+/// in the frame's coordinates is `normal`.
+pub fn set_hit(
+    hit: &mut HitRecord,
+    ray: &Ray,
+    frame: &Frame,
+    t: f64,
+    normal: Point,
+    (u, v): (f64, f64),
+) {
+    hit.t = t;
+    hit.point = ray.at(t);
+    hit.u = u;
+    hit.v = v;
+    hit.set_face_normal(ray, frame.vector_to_world(normal).unit_vector());
+}
@@ -0,0 +1,90 @@
+//! The GGX (Trowbridge-Reitz) microfacet distribution. Vectors are in a
+//! local frame whose y axis is the surface normal, as `Frame` builds, and
+//! `alpha` is the distribution's width, the square of perceptual roughness.
+
+use std::f64::consts::PI;
+
+use crate::point::Point;
+
+/// The smallest `alpha` used, so near-mirrors don't divide by zero.
+pub const MIN_ALPHA: f64 = 1e-3;
+
+/// The GGX width for a perceptual `roughness` from 0 to 1.
+pub fn alpha(roughness: f64) -> f64 {
+    (roughness * roughness).max(MIN_ALPHA)
+}
+
+/// The density of microfacet normals `h`, per unit area of the surface.
+pub fn d(h: &Point, alpha: f64) -> f64 {
+    if h.y <= 0.0 {
+        return 0.0;
+    }
+    let a2 = alpha * alpha;
+    let t = h.y * h.y * (a2 - 1.0) + 1.0;
+    a2 / (PI * t * t)
+}
+
+/// Smith's auxiliary function Λ for direction `v`.
+pub fn lambda(v: &Point, alpha: f64) -> f64 {
+    let cos2 = v.y * v.y;
+    if cos2 == 0.0 {
+        return f64::INFINITY;
+    }
+    let tan2 = (1.0 - cos2).max(0.0) / cos2;
+    ((1.0 + alpha * alpha * tan2).sqrt() - 1.0) / 2.0
+}
+
+/// The fraction of microfacets facing `v` that `v` can see.
+pub fn g1(v: &Point, alpha: f64) -> f64 {
+    1.0 / (1.0 + lambda(v, alpha))
+}
+
+/// The fraction of microfacets seen from both `wo` and `wi`, with the
+/// height-correlated form of Smith's masking-shadowing function.
+pub fn g2(wo: &Point, wi: &Point, alpha: f64) -> f64 {
+    1.0 / (1.0 + lambda(wo, alpha) + lambda(wi, alpha))
+}
+
+/// The density of mirroring `wo` into `wi` about a normal drawn by
+/// `sample_visible_normal`, per unit solid angle.
+pub fn reflection_pdf(wo: &Point, wi: &Point, alpha: f64) -> f64 {
+    if wo.y <= 0.0 || wi.y <= 0.0 {
+        return 0.0;
+    }
+    let h = (*wo + *wi).unit_vector();
+    g1(wo, alpha) * d(&h, alpha) / (4.0 * wo.y)
+}
+
+/// A microfacet normal visible from `wo`, drawn in proportion to its
+/// projected area from the uniform samples `u1` and `u2`, by Heitz's method
+/// in "Sampling the GGX Distribution of Visible Normals" (2018).
+pub fn sample_visible_normal(wo: &Point, alpha: f64, u1: f64, u2: f64) -> Point {
+    // Stretch the view so the distribution becomes a hemisphere.
+    let v = Point::new(alpha * wo.x, wo.y, alpha * wo.z).unit_vector();
+
+    let len2 = v.x * v.x + v.z * v.z;
+    let t1 = if len2 > 0.0 {
+        Point::new(-v.z, 0.0, v.x) / len2.sqrt()
+    } else {
+        Point::new(1.0, 0.0, 0.0)
+    };
+    // With y up rather than z, this order keeps t2 on the upper side.
+    let t2 = t1.cross(&v);
+
+    // A point on the disk, squashed onto the part of the hemisphere seen.
+    let r = u1.sqrt();
+    let phi = 2.0 * PI * u2;
+    let p1 = r * phi.cos();
+    let s = 0.5 * (1.0 + v.y);
+    let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+    let n = t1 * p1 + t2 * p2 + v * (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+
+    // Unstretch back onto the ellipsoid.
+    Point::new(alpha * n.x, n.y.max(0.0), alpha * n.z).unit_vector()
+}
+
+/// Schlick's approximation of the Fresnel reflectance for a reflectance
+/// `f0` at normal incidence.
+pub fn schlick(f0: f64, cosine: f64) -> f64 {
+    f0 + (1.0 - f0) * (1.0 - cosine.clamp(0.0, 1.0)).powi(5)
+}
@@ -0,0 +1,94 @@
+use std::io::{self, Write};
+
+use crate::ppm::PPM;
+
+/// Write the image's linear radiance, without gamma correction or clamping,
+/// as an uncompressed Radiance RGBE (.hdr) file.
+pub fn write<W: Write>(image: &PPM, mut w: W) -> io::Result<()> {
+    write!(
+        w,
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        image.height, image.width
+    )?;
+    for row in image.pixels.chunks(image.width * 3) {
+        let scanline = row
+            .chunks(3)
+            .flat_map(|p| rgbe(p[0], p[1], p[2]))
+            .collect::<Vec<u8>>();
+        w.write_all(&scanline)?;
+    }
+    w.flush()
+}
+
+/// Encode a colour as three 8 bit mantissas sharing one exponent.
+fn rgbe(r: f64, g: f64, b: f64) -> [u8; 4] {
+    let (r, g, b) = (r.max(0.0), g.max(0.0), b.max(0.0));
+    let v = r.max(g).max(b);
+    if !v.is_finite() || v < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+    // v = m * 2^e with m in [0.5, 1)
+    let mut e = v.log2().floor() as i32 + 1;
+    if v / 2_f64.powi(e) >= 1.0 {
+        e += 1;
+    }
+    let scale = 256.0 / 2_f64.powi(e);
+    [
+        (r * scale) as u8,
+        (g * scale) as u8,
+        (b * scale) as u8,
+        (e + 128).clamp(0, 255) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode([r, g, b, e]: [u8; 4]) -> [f64; 3] {
+        if e == 0 {
+            return [0.0; 3];
+        }
+        let scale = 2_f64.powi(e as i32 - 128) / 256.0;
+        [r as f64 * scale, g as f64 * scale, b as f64 * scale]
+    }
+
+    #[test]
+    fn encodes_exact_powers_of_two() {
+        assert_eq!(rgbe(1.0, 0.5, 0.25), [128, 64, 32, 129]);
+        assert_eq!(rgbe(4.0, 0.0, 0.0), [128, 0, 0, 131]);
+    }
+
+    #[test]
+    fn black_negative_and_non_finite_are_zero() {
+        assert_eq!(rgbe(0.0, 0.0, 0.0), [0, 0, 0, 0]);
+        assert_eq!(rgbe(-1.0, -2.0, 0.0), [0, 0, 0, 0]);
+        assert_eq!(rgbe(f64::NAN, 0.0, 0.0), [0, 0, 0, 0]);
+        assert_eq!(rgbe(f64::INFINITY, 1.0, 1.0), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn round_trips_within_mantissa_precision() {
+        for &(r, g, b) in &[(0.3, 0.6, 0.9), (12.5, 3.0, 0.01), (1e-3, 2e-3, 5e-4)] {
+            let [dr, dg, db] = decode(rgbe(r, g, b));
+            let max = f64::max(r, f64::max(g, b));
+            // Each channel is quantised to 1/256 of the brightest one's scale.
+            for (a, d) in [(r, dr), (g, dg), (b, db)] {
+                assert!((a - d).abs() <= max / 128.0, "{} decoded as {}", a, d);
+            }
+        }
+    }
+
+    #[test]
+    fn writes_header_and_one_pixel_per_four_bytes() {
+        let mut image = PPM::new(3, 2);
+        image.pixels[0] = 1.0;
+        let mut written = Vec::new();
+        write(&image, &mut written).unwrap();
+        let header = "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 3\n";
+        assert!(written.starts_with(header.as_bytes()));
+        let raster = &written[header.len()..];
+        assert_eq!(raster.len(), 3 * 2 * 4);
+        assert_eq!(raster[..4], [128, 0, 0, 129]);
+    }
+}
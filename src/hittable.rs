@@ -1,3 +1,6 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
 use crate::material::Material;
 use crate::point::Point;
 use crate::ray::Ray;
@@ -7,6 +10,9 @@ pub struct HitRecord {
     pub point: Point,
     pub normal: Point,
     pub t: f64,
+    /// Surface texture coordinates of the hit.
+    pub u: f64,
+    pub v: f64,
     pub front_face: bool,
     pub material: Box<Material>,
 }
@@ -22,6 +28,86 @@ impl HitRecord {
     }
 }
 
+/// A stretch of a ray inside a solid, from the hit where it enters to the hit
+/// where it leaves.
+#[derive(Clone)]
+pub struct Span {
+    pub enter: HitRecord,
+    pub exit: HitRecord,
+}
+
 pub trait Hittable {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool;
+    fn bounding_box(&self) -> Option<Aabb>;
+
+    /// The spans of the ray between `t_min` and `t_max` that are inside the
+    /// object, in order, for constructive solid geometry. This only makes
+    /// sense for closed solids. A span that begins before `t_min` or ends
+    /// after `t_max` is cut off there by a hit record with no surface.
+    ///
+    /// By default the spans are found by following the ray from hit to hit.
+    fn spans(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<Span> {
+        let bound = |t| HitRecord {
+            t,
+            ..HitRecord::default()
+        };
+        let mut spans = Vec::new();
+        let mut enter = None;
+        let mut rec = HitRecord::default();
+        let mut t = t_min;
+        while self.hit(ray, t, t_max, &mut rec) {
+            t = rec.t;
+            if rec.front_face {
+                enter = Some(rec.clone());
+            } else {
+                spans.push(Span {
+                    enter: enter.take().unwrap_or_else(|| bound(t_min)),
+                    exit: rec.clone(),
+                });
+            }
+        }
+        if let Some(enter) = enter {
+            spans.push(Span {
+                enter,
+                exit: bound(t_max),
+            });
+        }
+        spans
+    }
+
+    /// The probability density, per unit solid angle, with which `random`
+    /// picks `direction` from `origin`. Objects that can't be sampled as
+    /// lights return 0.
+    fn pdf_value(&self, _origin: &Point, _direction: &Point) -> f64 {
+        0.0
+    }
+
+    /// A random direction from `origin` towards the object, for sampling it
+    /// as a light.
+    fn random(&self, _origin: &Point) -> Point {
+        Point::new(1.0, 0.0, 0.0)
+    }
+}
+
+/// Shared objects, so one can be placed in the world several times.
+impl<T: Hittable + ?Sized> Hittable for Arc<T> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        (**self).hit(ray, t_min, t_max, rec)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        (**self).bounding_box()
+    }
+
+    fn spans(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<Span> {
+        (**self).spans(ray, t_min, t_max)
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Point) -> f64 {
+        (**self).pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point) -> Point {
+        (**self).random(origin)
+    }
 }
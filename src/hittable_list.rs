@@ -1,6 +1,10 @@
+use crate::aabb::Aabb;
+use crate::bvh::BvhNode;
 use crate::hittable::HitRecord;
 use crate::hittable::Hittable;
+use crate::point::Point;
 use crate::ray::Ray;
+use rand::seq::SliceRandom;
 
 #[derive(Default)]
 pub struct HittableList {
@@ -21,6 +25,21 @@ impl HittableList {
     pub fn add(&mut self, object: Box<dyn Hittable + Send + Sync>) {
         self.objects.push(object);
     }
+
+    /// Put every object that has a bounding box into a `BvhNode`. Objects
+    /// without one stay in the returned list next to the hierarchy.
+    pub fn into_bvh(self) -> HittableList {
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = self
+            .objects
+            .into_iter()
+            .partition(|o| o.bounding_box().is_some());
+
+        let mut list = HittableList { objects: unbounded };
+        if !bounded.is_empty() {
+            list.add(Box::new(BvhNode::new(bounded)));
+        }
+        list
+    }
 }
 
 impl Hittable for HittableList {
@@ -38,4 +57,30 @@ impl Hittable for HittableList {
         }
         hit_anything
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let mut boxes = self.objects.iter().map(|o| o.bounding_box());
+        let first = boxes.next()??;
+        boxes.try_fold(first, |acc, b| Some(acc.union(&b?)))
+    }
+
+    /// Each object is sampled equally often, so the density is their mean.
+    fn pdf_value(&self, origin: &Point, direction: &Point) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self
+            .objects
+            .iter()
+            .map(|o| o.pdf_value(origin, direction))
+            .sum();
+        sum / self.objects.len() as f64
+    }
+
+    fn random(&self, origin: &Point) -> Point {
+        match self.objects.choose(&mut rand::thread_rng()) {
+            Some(object) => object.random(origin),
+            None => Point::new(1.0, 0.0, 0.0),
+        }
+    }
 }
@@ -0,0 +1,38 @@
+pub mod aabb;
+pub mod background;
+pub mod bvh;
+pub mod camera;
+pub mod cone;
+pub mod constant_medium;
+pub mod csg;
+pub mod cylinder;
+pub mod disk;
+pub mod frame;
+pub mod ggx;
+pub mod hdr;
+pub mod hittable;
+pub mod hittable_list;
+pub mod mat4;
+pub mod material;
+pub mod mesh;
+pub mod moving_sphere;
+pub mod obj;
+pub mod pdf;
+pub mod perlin;
+pub mod pixel;
+pub mod plane;
+pub mod point;
+pub mod ppm;
+pub mod principled;
+pub mod quad;
+pub mod quat;
+pub mod ray;
+pub mod rect;
+pub mod scene;
+pub mod spectrum;
+pub mod sphere;
+pub mod texture;
+pub mod tonemap;
+pub mod torus;
+pub mod transform;
+pub mod triangle;
@@ -1,55 +1,47 @@
-#![feature(clamp)]
-mod pixel;
-use std::path::Path;
-use std::sync::mpsc::{Receiver, Sender};
-use std::time::Duration;
-
-use hittable::Hittable;
-use ppm::PPM;
-use show_image::ImageData;
-
-use crate::pixel::Pixel;
-use crate::{pixel::PixelSlice, point::Point};
-mod point;
-mod ppm;
-
-mod ray;
-use crate::ray::Ray;
-
-mod hittable;
-use crate::hittable::HitRecord;
-
-mod hittable_list;
-use crate::hittable_list::HittableList;
+mod cli;
 
-mod sphere;
-use crate::sphere::Sphere;
-
-mod camera;
-use crate::camera::Camera;
-
-mod material;
-use crate::material::Material;
+use std::path::PathBuf;
+use std::process;
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::Instant;
 
+use clap::Parser;
 use rand::Rng;
 use rayon::prelude::*;
-use show_image::{make_window, KeyCode};
+use raytracing_in_one_weekend::background::Background;
+use raytracing_in_one_weekend::hittable::{HitRecord, Hittable};
+use raytracing_in_one_weekend::hittable_list::HittableList;
+use raytracing_in_one_weekend::material::{Material, Medium};
+use raytracing_in_one_weekend::pdf::Pdf;
+use raytracing_in_one_weekend::pixel::{Pixel, PixelSlice};
+use raytracing_in_one_weekend::plane::Plane;
+use raytracing_in_one_weekend::point::Point;
+use raytracing_in_one_weekend::ppm::PPM;
+use raytracing_in_one_weekend::ray::Ray;
+use raytracing_in_one_weekend::scene::Scene;
+use raytracing_in_one_weekend::spectrum;
+use raytracing_in_one_weekend::sphere::Sphere;
 
 use std::sync::mpsc::channel;
 use std::thread;
 
 use pbr::ProgressBar;
+use std::io;
+#[cfg(feature = "window")]
+use std::io::Stderr;
+
+use crate::cli::{Cli, Command, RenderArgs};
 
 fn random_scene() -> HittableList {
     let mut world = HittableList::default();
 
     let ground_material = Box::new(Material::Lambertian {
-        albedo: Pixel::new(0.5, 0.5, 0.5),
+        albedo: Pixel::new(0.5, 0.5, 0.5).into(),
     });
     // Ground
-    world.add(Box::new(Sphere::new(
-        Point::new(0.0, -1000.0, 0.0),
-        1000.0,
+    world.add(Box::new(Plane::new(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(0.0, 1.0, 0.0),
         ground_material,
     )));
 
@@ -58,44 +50,36 @@ fn random_scene() -> HittableList {
     for a in -11..11 {
         for b in -11..11 {
             let choose_mat = rng.gen_range(0.0, 1.0);
-            let center = Point{
-                x: a as f64 + 0.9* rng.gen_range(0.0, 1.0),
+            let center = Point {
+                x: a as f64 + 0.9 * rng.gen_range(0.0, 1.0),
                 y: 0.2,
-                z:b as f64 + 0.9 * rng.gen_range(0.0, 1.0),
+                z: b as f64 + 0.9 * rng.gen_range(0.0, 1.0),
             };
 
-            if (center - Point::new(4.0,0.2,0.0)).length() > 0.9 {
+            if (center - Point::new(4.0, 0.2, 0.0)).length() > 0.9 {
                 if choose_mat < 0.8 {
                     let albedo = Pixel::random() * Pixel::random();
                     let sphere_material = Box::new(Material::Lambertian {
-                        albedo
+                        albedo: albedo.into(),
                     });
-                    world.add(Box::new(Sphere::new(
-                        center,
-                        0.2,
-                        sphere_material
-                    )));
+                    world.add(Box::new(Sphere::new(center, 0.2, sphere_material)));
                 } else if choose_mat < 0.95 {
                     let albedo = Pixel::random_range(0.5, 1.0);
                     let fuzz = rng.gen_range(0.0, 0.5);
                     let sphere_material = Box::new(Material::Metal {
-                        albedo, fuzz
+                        albedo: albedo.into(),
+                        fuzz,
                     });
-                    world.add(Box::new(Sphere::new(
-                        center,
-                        0.2,
-                        sphere_material
-                    )));
+                    world.add(Box::new(Sphere::new(center, 0.2, sphere_material)));
                 } else {
                     let ref_idx = rng.gen_range(1.3, 1.8);
                     let sphere_material = Box::new(Material::Dielectric {
-                        ref_idx
+                        ref_idx,
+                        absorption: Pixel::default(),
+                        roughness: 0.0,
+                        dispersion: None,
                     });
-                    world.add(Box::new(Sphere::new(
-                        center,
-                        0.2,
-                        sphere_material
-                    )));
+                    world.add(Box::new(Sphere::new(center, 0.2, sphere_material)));
                 }
             }
         }
@@ -105,97 +89,329 @@ fn random_scene() -> HittableList {
     world.add(Box::new(Sphere::new(
         Point::new(0.0, 1.0, 0.0),
         1.0,
-        Box::new(Material::Dielectric { ref_idx: 1.5 }),
+        Box::new(Material::Dielectric {
+            ref_idx: 1.5,
+            absorption: Pixel::default(),
+            roughness: 0.0,
+            dispersion: None,
+        }),
     )));
 
-
     // Centre
     world.add(Box::new(Sphere::new(
         Point::new(-4.0, 1.0, 0.0),
         1.0,
         Box::new(Material::Lambertian {
-            albedo: Pixel::new(0.9, 0.2, 0.1),
+            albedo: Pixel::new(0.9, 0.2, 0.1).into(),
         }),
     )));
 
-
     // Right
     world.add(Box::new(Sphere::new(
         Point::new(4.0, 1.0, 0.0),
         1.0,
         Box::new(Material::Metal {
-            albedo: Pixel::new(0.7, 0.6, 0.5),
+            albedo: Pixel::new(0.7, 0.6, 0.5).into(),
             fuzz: 0.0,
         }),
     )));
     world
 }
 
-fn ray_color(ray: &Ray, world: &HittableList, depth: usize) -> Pixel {
+/// The power heuristic for multiple importance sampling: the weight of a
+/// sample drawn with density `pdf` that another strategy draws with density
+/// `other`.
+fn power_heuristic(pdf: f64, other: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other * other);
+    if a + b == 0.0 {
+        0.0
+    } else {
+        a / (a + b)
+    }
+}
+
+/// Light arriving at a hit straight from a randomly chosen light, weighted
+/// against finding the light by scattering.
+fn sample_light(
+    ray: &Ray,
+    hit_record: &HitRecord,
+    world: &HittableList,
+    lights: &HittableList,
+    media: &[Medium],
+) -> Pixel {
+    let light_pdf = Pdf::Hittable {
+        objects: lights,
+        origin: hit_record.point,
+    };
+    let direction = light_pdf.generate();
+    let light_pdf = light_pdf.value(&direction);
+    let scatter_pdf = hit_record.material.pdf(ray, hit_record, &direction);
+    if light_pdf <= 0.0 || scatter_pdf <= 0.0 {
+        return Pixel::new(0.0, 0.0, 0.0);
+    }
+
+    let shadow_ray = ray.scattered(hit_record.point, direction);
+    let mut light_hit = HitRecord::default();
+    if !world.hit(&shadow_ray, 0.001, f64::INFINITY, &mut light_hit)
+        || !on_light(&shadow_ray, light_hit.t, lights)
+    {
+        return Pixel::new(0.0, 0.0, 0.0);
+    }
+    let emitted = light_hit
+        .material
+        .emitted(light_hit.u, light_hit.v, &light_hit.point);
+    let weight = power_heuristic(light_pdf, scatter_pdf);
+    let spectral = |color| spectrum::at_wavelength(color, ray.wavelength);
+    spectral(emitted)
+        * spectral(transmittance(media, light_hit.t * direction.length()))
+        * spectral(hit_record.material.eval(ray, hit_record, &direction))
+        * (weight / light_pdf)
+}
+
+/// Whether the first hit along `ray`, at `t`, is on one of `lights`. Other
+/// emitters are never sampled directly, so they're only found by scattering.
+fn on_light(ray: &Ray, t: f64, lights: &HittableList) -> bool {
+    let mut light_hit = HitRecord::default();
+    lights.hit(ray, 0.001, t * (1.0 + 1e-9), &mut light_hit)
+}
+
+/// The fraction of light left after `distance` through the innermost of
+/// `media`.
+fn transmittance(media: &[Medium], distance: f64) -> Pixel {
+    match media.last() {
+        Some(medium) => medium.transmittance(distance),
+        None => Pixel::new(1.0, 1.0, 1.0),
+    }
+}
+
+/// The dielectrics a ray is inside after passing into or out of `medium`.
+fn cross(media: &[Medium], medium: Medium, entering: bool) -> Vec<Medium> {
+    let mut media = media.to_vec();
+    if entering {
+        media.push(medium);
+    } else if let Some(index) = media.iter().rposition(|m| *m == medium) {
+        media.remove(index);
+    }
+    media
+}
+
+/// `scatter_pdf` is the density with which the previous hit picked `ray`
+/// when lights were also sampled there, and `None` for camera rays and ones
+/// off mirrors and glass. `media` are the dielectrics `ray` travels through,
+/// innermost last. In a spectral render every channel of the result holds
+/// the radiance at `ray`'s wavelength.
+fn ray_color(
+    ray: &Ray,
+    world: &HittableList,
+    lights: &HittableList,
+    background: &Background,
+    depth: usize,
+    scatter_pdf: Option<f64>,
+    media: &[Medium],
+) -> Pixel {
     let mut hit_record = HitRecord::default();
     if depth == 0 {
         return Pixel::new(0.0, 0.0, 0.0);
     }
+    let spectral = |color| spectrum::at_wavelength(color, ray.wavelength);
 
     if world.hit(ray, 0.001, f64::INFINITY, &mut hit_record) {
-        let mut scattered = Ray::default();
-        let mut attenuation = Pixel::default();
-        if hit_record
-            .material
-            .scatter(ray, &hit_record, &mut attenuation, &mut scattered)
+        let absorbed = spectral(transmittance(media, hit_record.t * ray.direction.length()));
+        let mut emitted = spectral(hit_record.material.emitted(
+            hit_record.u,
+            hit_record.v,
+            &hit_record.point,
+        ));
+        match scatter_pdf {
+            Some(pdf) if on_light(ray, hit_record.t, lights) => {
+                let light_pdf = lights.pdf_value(&ray.origin, &ray.direction);
+                emitted = emitted * power_heuristic(pdf, light_pdf);
+            }
+            _ => {}
+        }
+        // Lights are only sampled where a scattered ray could still reach
+        // them, so both strategies cover the same paths. This holds even when
+        // the scattered direction is rejected, as it is then simply dark.
+        let direct = if depth > 1
+            && !lights.objects.is_empty()
+            && hit_record.material.has_pdf(ray, &hit_record)
         {
-            let out = ray_color(&scattered, world, depth - 1) * attenuation;
-
-            return out;
+            sample_light(ray, &hit_record, world, lights, media)
+        } else {
+            Pixel::new(0.0, 0.0, 0.0)
+        };
+        if let Some(sample) = hit_record.material.scatter(ray, &hit_record, media) {
+            let inside;
+            let media = match hit_record.material.medium(ray.wavelength) {
+                Some(medium) if sample.crossed => {
+                    inside = cross(media, medium, hit_record.front_face);
+                    &inside[..]
+                }
+                _ => media,
+            };
+            let out = ray_color(
+                &sample.ray,
+                world,
+                lights,
+                background,
+                depth - 1,
+                sample.pdf,
+                media,
+            ) * spectral(sample.attenuation);
+
+            return (emitted + direct + out) * absorbed;
         }
-        return Pixel::new(0.0, 0.0, 0.0);
+        return (emitted + direct) * absorbed;
     }
-    let unit_direction = ray.direction.unit_vector();
-    let t = 0.5 * (unit_direction.y + 1.0);
-    (Pixel {
-        r: 1.0,
-        g: 1.0,
-        b: 1.0,
-    } * (1.0 - t))
-        + (Pixel {
-            r: 0.4,
-            g: 0.5,
-            b: 1.0,
-        } * t)
+    spectral(background.color(ray)) * spectral(transmittance(media, f64::INFINITY))
 }
 
 fn main() {
-    // Image
-    let aspect_ratio = 16.0 / 9.0;
-    //let width = 800;
-    let width = 3840;
-    let height = (width as f64 / aspect_ratio) as usize;
-    let samples_per_pixel = 500;
-    let max_depth = 200;
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Render {
+            settings,
+            output,
+            headless,
+        } => {
+            let headless = headless || !cfg!(feature = "window");
+            render(load_scene(&settings, false), Some(output), headless);
+        }
+        Command::Preview {
+            settings,
+            output,
+            headless,
+        } => {
+            let headless = headless || !cfg!(feature = "window");
+            if headless && output.is_none() {
+                eprintln!("A preview without a window needs --output");
+                process::exit(1);
+            }
+            render(load_scene(&settings, true), output, headless);
+        }
+        Command::Info { settings } => {
+            let scene = load_scene(&settings, false);
+            let image = &scene.image;
+            let camera = &scene.camera;
+            println!("Image:   {}x{}", image.width, image.height());
+            println!("Samples: {} per pixel", image.samples_per_pixel);
+            println!("Depth:   {} bounces", image.max_depth);
+            println!("Tone:    {} at {:+} stops", image.tone_map, image.exposure);
+            println!(
+                "Colour:  {}",
+                if image.spectral { "spectral" } else { "rgb" }
+            );
+            println!(
+                "Camera:  from {} looking at {} (up {})",
+                camera.lookfrom, camera.lookat, camera.vup
+            );
+            println!(
+                "Lens:    vfov {} aperture {} focus distance {}",
+                camera.vfov, camera.aperture, camera.focus_dist
+            );
+            println!(
+                "Shutter: open {} to {}",
+                camera.shutter_open, camera.shutter_close
+            );
+            println!("Sky:     {}", scene.background);
+            println!("Objects: {}", scene.world.objects.len());
+            println!("Lights:  {}", scene.lights.objects.len());
+            match scene.world.bounding_box() {
+                Some(bbox) => println!("Bounds:  {} to {}", bbox.min, bbox.max),
+                None => println!("Bounds:  unbounded"),
+            }
+        }
+        Command::Bench { settings } => bench(load_scene(&settings, false)),
+    }
+}
 
-    // World
-    let world = random_scene();
+fn bench(scene: Scene) {
+    let width = scene.image.width;
+    let height = scene.image.height();
+    let camera = scene.camera();
+    let rays = (0..height)
+        .flat_map(|j| (0..width).map(move |i| (i, j)))
+        .map(|(i, j)| {
+            camera.get_ray(
+                i as f64 / (width - 1) as f64,
+                j as f64 / (height - 1) as f64,
+            )
+        })
+        .collect::<Vec<Ray>>();
+
+    let trace = |world: &HittableList| {
+        let start = Instant::now();
+        let hits = rays
+            .par_iter()
+            .filter(|ray| world.hit(ray, 0.001, f64::INFINITY, &mut HitRecord::default()))
+            .count();
+        (start.elapsed(), hits)
+    };
+
+    let list = scene.world;
+    let (list_time, list_hits) = trace(&list);
+
+    let start = Instant::now();
+    let bvh = list.into_bvh();
+    let build_time = start.elapsed();
+    let (bvh_time, bvh_hits) = trace(&bvh);
+
+    println!("Rays:    {} ({}x{})", rays.len(), width, height);
+    println!("List:    {:?}, {} hits", list_time, list_hits);
+    println!(
+        "BVH:     {:?}, {} hits, built in {:?}",
+        bvh_time, bvh_hits, build_time
+    );
+    println!(
+        "Speedup: {:.1}x",
+        list_time.as_secs_f64() / bvh_time.as_secs_f64()
+    );
+}
+
+fn load_scene(settings: &RenderArgs, preview: bool) -> Scene {
+    let mut scene = match &settings.scene {
+        Some(path) => Scene::load(path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        }),
+        None => Scene::new(random_scene()),
+    };
+
+    if preview {
+        scene.image.width = scene.image.width.min(800);
+        scene.image.samples_per_pixel = scene.image.samples_per_pixel.min(10);
+        scene.image.max_depth = scene.image.max_depth.min(10);
+    }
+    settings.apply(&mut scene);
+
+    if let Err(e) = scene.validate() {
+        eprintln!("Invalid settings: {}", e);
+        process::exit(1);
+    }
+    scene
+}
+
+fn render(scene: Scene, output: Option<PathBuf>, headless: bool) {
+    // Image
+    let width = scene.image.width;
+    let height = scene.image.height();
+    let samples_per_pixel = scene.image.samples_per_pixel;
+    let max_depth = scene.image.max_depth;
+    let spectral = scene.image.spectral;
 
     // Camera
-    let lookfrom = Point::new(13.0, 2.0, 3.0);
-    let lookat = Point::new(0.0, 0.0, 0.0);
-    let vup = Point::new(0.0, 1.0, 0.0);
-    let focus_dist = 10.0;
-    let aperture = 0.1;
-
-    let camera = Camera::new(
-        lookfrom,
-        lookat,
-        vup,
-        20.0,
-        aspect_ratio,
-        aperture,
-        focus_dist,
-    );
+    let camera = scene.camera();
+
+    // World
+    let background = scene.background;
+    let lights = scene.lights;
+    let world = scene.world.into_bvh();
 
     let mut image = PPM::new(width, height);
+    image.tone_map = scene.image.tone_map();
 
-    let (handle, sender, shutdown_receiver) = image_thread(image.clone());
+    let (handle, sender, shutdown_receiver) = image_thread(image.clone(), output, headless);
 
     image
         .pixels
@@ -207,13 +423,20 @@ fn main() {
             let mut rng = rand::thread_rng();
             for (column_index, pixel) in pixel_row.1.chunks_mut(3).enumerate() {
                 let mut p = PixelSlice { s: pixel };
-                for _sample in 0..=samples_per_pixel {
+                for _sample in 0..samples_per_pixel {
                     let u = (column_index as f64 + rng.gen_range(0.0, 1.0)) / (width - 1) as f64;
 
                     let v = (row_index as f64 + rng.gen_range(0.0, 1.0)) / (height - 1) as f64;
 
-                    let ray = camera.get_ray(u, v);
-                    p += ray_color(&ray, &world, max_depth);
+                    let mut ray = camera.get_ray(u, v);
+                    if spectral {
+                        ray.wavelength = Some(spectrum::random_wavelength());
+                    }
+                    let color = ray_color(&ray, &world, &lights, &background, max_depth, None, &[]);
+                    p += match ray.wavelength {
+                        Some(wavelength) => spectrum::to_rgb(color.r, wavelength),
+                        None => color,
+                    };
                 }
                 p *= 1.0 / samples_per_pixel as f64;
             }
@@ -221,7 +444,7 @@ fn main() {
             s.send((row_index, pixel_row.1.to_vec())).unwrap();
         });
 
-    println!("Finished Rendering");
+    eprintln!("Finished Rendering");
 
     shutdown_receiver.recv().unwrap();
     handle.join().unwrap()
@@ -229,55 +452,120 @@ fn main() {
 
 type RowData = (usize, Vec<f64>);
 
-fn image_thread(mut image: PPM) -> (thread::JoinHandle<()>, Sender<RowData>, Receiver<usize>) {
+fn image_thread(
+    mut image: PPM,
+    output: Option<PathBuf>,
+    headless: bool,
+) -> (thread::JoinHandle<()>, Sender<RowData>, Receiver<usize>) {
     let (sender, receiver): (Sender<RowData>, Receiver<RowData>) = channel();
     let (shutdown_sender, shutdown_reciever) = channel();
     let handle = thread::spawn(move || {
-        let window = make_window("ray_tracing_in_one_weekend").unwrap();
-        let mut pb = ProgressBar::new(image.height as u64);
+        let mut pb = ProgressBar::on(io::stderr(), image.height as u64);
         pb.format("╢▌▌░╟");
-        let mut update = true;
-
-        loop {
-            match receiver.try_recv() {
-                Ok(inner) => {
-                    let row_start: usize =
-                        ((image.height * image.width) - (inner.0 + 1) * image.width) * 3;
-                    let row_end: usize =
-                        ((image.height * image.width) - (inner.0) * image.width) * 3;
-
-                    image.pixels.splice(row_start..row_end, inner.1);
-                    pb.inc();
-                    update = true;
-                }
-                Err(std::sync::mpsc::TryRecvError::Empty) => {
-                    if update {
-                        window.set_image(&image, "image-001").unwrap();
-                        update = false
-                    }
-                }
-                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                    window.set_image(&image, "image-001").unwrap();
-                    break;
-                }
+
+        if headless {
+            for (row_index, row) in receiver {
+                image.set_row(row_index, &row);
+                pb.inc();
             }
+            pb.finish_print("Finished Rendering Image");
+        } else {
+            #[cfg(feature = "window")]
+            display_window(&mut image, receiver, &mut pb);
         }
 
-        pb.finish_print("Finished Displaying Image");
-        while let Ok(event) = window.wait_key(Duration::from_millis(1000)) {
-            if let Some(event) = event {
-                if event.key == KeyCode::Escape {
-                    break;
-                }
+        if let Some(output) = output {
+            let saved = if output.as_os_str() == "-" {
+                image
+                    .write_p6(io::stdout().lock())
+                    .map_err(|e| e.to_string())
+            } else {
+                image.save(&output)
+            };
+            if let Err(e) = saved {
+                eprintln!("Failed to save {}: {}", output.display(), e);
             }
         }
-
-        let info = image.info().unwrap();
-        let data = image.data();
-
-        show_image::save_image(Path::new("out.png"), &data, info);
-        show_image::stop().ok();
         shutdown_sender.send(1).unwrap();
     });
     (handle, sender, shutdown_reciever)
 }
+
+/// Show rows in a window as they arrive, then wait for Escape to be pressed.
+#[cfg(feature = "window")]
+fn display_window(image: &mut PPM, receiver: Receiver<RowData>, pb: &mut ProgressBar<Stderr>) {
+    use show_image::{make_window, KeyCode};
+    use std::sync::mpsc::TryRecvError;
+    use std::time::Duration;
+
+    let window = make_window("ray_tracing_in_one_weekend").unwrap();
+    let mut update = true;
+
+    loop {
+        match receiver.try_recv() {
+            Ok(inner) => {
+                image.set_row(inner.0, &inner.1);
+                pb.inc();
+                update = true;
+            }
+            Err(TryRecvError::Empty) => {
+                if update {
+                    window.set_image(&*image, "image-001").unwrap();
+                    update = false
+                }
+            }
+            Err(TryRecvError::Disconnected) => {
+                window.set_image(&*image, "image-001").unwrap();
+                break;
+            }
+        }
+    }
+
+    pb.finish_print("Finished Displaying Image");
+    while let Ok(event) = window.wait_key(Duration::from_millis(1000)) {
+        if let Some(event) = event {
+            if event.key == KeyCode::Escape {
+                break;
+            }
+        }
+    }
+    show_image::stop().ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn medium(ref_idx: f64) -> Medium {
+        Medium {
+            ref_idx,
+            absorption: Pixel::new(0.0, 0.0, 0.0),
+        }
+    }
+
+    #[test]
+    fn crossing_pushes_and_pops_media() {
+        let (water, glass) = (medium(1.33), medium(1.5));
+        let inside = cross(&cross(&[], water, true), glass, true);
+        assert!(inside == [water, glass]);
+        // Volumes needn't be left in the order they were entered.
+        assert!(cross(&inside, water, false) == [glass]);
+        assert!(cross(&inside, glass, false) == [water]);
+        // Leaving a medium the ray was never counted in changes nothing.
+        assert!(cross(&inside, medium(2.4), false) == inside);
+    }
+
+    #[test]
+    fn transmittance_uses_the_innermost_medium() {
+        let tinted = Medium {
+            ref_idx: 1.5,
+            absorption: Pixel::new(1.0, 1.0, 1.0),
+        };
+        assert_eq!(transmittance(&[], 10.0), Pixel::new(1.0, 1.0, 1.0));
+        assert_eq!(
+            transmittance(&[medium(1.33), tinted], 1.0).r,
+            (-1.0_f64).exp()
+        );
+        assert_eq!(transmittance(&[tinted, medium(1.33)], 1.0).r, 1.0);
+    }
+}
@@ -0,0 +1,381 @@
+use std::ops::Mul;
+
+use crate::point::Point;
+use crate::quat::Quat;
+
+/// A 4x4 matrix in row-major order, acting on column vectors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat4 {
+    pub m: [[f64; 4]; 4],
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mat4 {
+    pub fn new(m: [[f64; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn identity() -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn translation(offset: Point) -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, offset.x],
+            [0.0, 1.0, 0.0, offset.y],
+            [0.0, 0.0, 1.0, offset.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn scaling(factors: Point) -> Self {
+        Self::new([
+            [factors.x, 0.0, 0.0, 0.0],
+            [0.0, factors.y, 0.0, 0.0],
+            [0.0, 0.0, factors.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// A right-handed rotation of `angle` radians about `axis`.
+    pub fn rotation(axis: Point, angle: f64) -> Self {
+        let Point { x, y, z } = axis.unit_vector();
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        Self::new([
+            [t * x * x + c, t * x * y - s * z, t * x * z + s * y, 0.0],
+            [t * x * y + s * z, t * y * y + c, t * y * z - s * x, 0.0],
+            [t * x * z - s * y, t * y * z + s * x, t * z * z + c, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// A right-handed view matrix for a camera at `eye` looking at `target`,
+    /// as `gluLookAt` builds. It maps world space to camera space, where the
+    /// camera looks down -z with y up.
+    pub fn look_at(eye: Point, target: Point, up: Point) -> Self {
+        let f = (target - eye).unit_vector();
+        let s = f.cross(&up).unit_vector();
+        let u = s.cross(&f);
+        Self::new([
+            [s.x, s.y, s.z, -s.dot(&eye)],
+            [u.x, u.y, u.z, -u.dot(&eye)],
+            [-f.x, -f.y, -f.z, f.dot(&eye)],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// An OpenGL style projection with a vertical field of view of `fovy`
+    /// radians, mapping the view frustum between `near` and `far` onto the
+    /// cube from -1 to 1.
+    pub fn perspective(fovy: f64, aspect: f64, near: f64, far: f64) -> Self {
+        let f = 1.0 / (fovy / 2.0).tan();
+        Self::new([
+            [f / aspect, 0.0, 0.0, 0.0],
+            [0.0, f, 0.0, 0.0],
+            [
+                0.0,
+                0.0,
+                (far + near) / (near - far),
+                2.0 * far * near / (near - far),
+            ],
+            [0.0, 0.0, -1.0, 0.0],
+        ])
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let m = &self.m;
+        // Laplace expansion by the 2x2 minors of the top and bottom rows.
+        let s0 = m[0][0] * m[1][1] - m[1][0] * m[0][1];
+        let s1 = m[0][0] * m[1][2] - m[1][0] * m[0][2];
+        let s2 = m[0][0] * m[1][3] - m[1][0] * m[0][3];
+        let s3 = m[0][1] * m[1][2] - m[1][1] * m[0][2];
+        let s4 = m[0][1] * m[1][3] - m[1][1] * m[0][3];
+        let s5 = m[0][2] * m[1][3] - m[1][2] * m[0][3];
+        let c0 = m[2][0] * m[3][1] - m[3][0] * m[2][1];
+        let c1 = m[2][0] * m[3][2] - m[3][0] * m[2][2];
+        let c2 = m[2][0] * m[3][3] - m[3][0] * m[2][3];
+        let c3 = m[2][1] * m[3][2] - m[3][1] * m[2][2];
+        let c4 = m[2][1] * m[3][3] - m[3][1] * m[2][3];
+        let c5 = m[2][2] * m[3][3] - m[3][2] * m[2][3];
+        s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut out = [[0.0; 4]; 4];
+        for (i, row) in out.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Self::new(out)
+    }
+
+    /// The inverse by Gauss-Jordan elimination, or `None` if the matrix is
+    /// singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inv = Self::identity().m;
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for j in 0..4 {
+                a[col][j] *= scale;
+                inv[col][j] *= scale;
+            }
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+                let factor = a[row][col];
+                for j in 0..4 {
+                    a[row][j] -= factor * a[col][j];
+                    inv[row][j] -= factor * inv[col][j];
+                }
+            }
+        }
+        Some(Self::new(inv))
+    }
+
+    /// Transform a position, including the translation.
+    pub fn transform_point(&self, p: Point) -> Point {
+        let m = &self.m;
+        let x = m[0][0] * p.x + m[0][1] * p.y + m[0][2] * p.z + m[0][3];
+        let y = m[1][0] * p.x + m[1][1] * p.y + m[1][2] * p.z + m[1][3];
+        let z = m[2][0] * p.x + m[2][1] * p.y + m[2][2] * p.z + m[2][3];
+        let w = m[3][0] * p.x + m[3][1] * p.y + m[3][2] * p.z + m[3][3];
+        if w == 1.0 {
+            Point::new(x, y, z)
+        } else {
+            Point::new(x / w, y / w, z / w)
+        }
+    }
+
+    /// Transform a direction, ignoring the translation.
+    pub fn transform_vector(&self, v: Point) -> Point {
+        let m = &self.m;
+        Point::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+impl From<Quat> for Mat4 {
+    fn from(q: Quat) -> Mat4 {
+        let Quat { w, x, y, z } = q.normalize();
+        Mat4::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, rhs: Mat4) -> Mat4 {
+        let mut out = [[0.0; 4]; 4];
+        for (i, row) in out.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Mat4::new(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn assert_mat_close(a: &Mat4, b: &Mat4) {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!(
+                    (a.m[i][j] - b.m[i][j]).abs() < 1e-9,
+                    "{:?} != {:?} at [{}][{}]",
+                    a,
+                    b,
+                    i,
+                    j
+                );
+            }
+        }
+    }
+
+    fn assert_point_close(a: Point, b: Point) {
+        assert!((a - b).length() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    const GENERAL: [[f64; 4]; 4] = [
+        [1.0, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 8.0],
+        [2.0, 6.0, 4.0, 8.0],
+        [3.0, 1.0, 1.0, 2.0],
+    ];
+
+    #[test]
+    fn determinant_of_known_matrices() {
+        assert_eq!(Mat4::identity().determinant(), 1.0);
+        assert_eq!(Mat4::scaling(Point::new(2.0, 3.0, 4.0)).determinant(), 24.0);
+        assert_eq!(Mat4::new(GENERAL).determinant(), 72.0);
+        let m = Mat4::new([
+            [2.0, 0.0, 1.0, 0.0],
+            [1.0, 3.0, 0.0, 1.0],
+            [0.0, 1.0, 4.0, 0.0],
+            [1.0, 0.0, 0.0, 5.0],
+        ]);
+        assert_eq!(m.determinant(), 124.0);
+    }
+
+    #[test]
+    fn inverse_times_matrix_is_identity() {
+        let matrices = [
+            Mat4::new(GENERAL),
+            Mat4::translation(Point::new(1.0, -2.0, 3.0))
+                * Mat4::rotation(Point::new(1.0, 1.0, 0.0), 0.7)
+                * Mat4::scaling(Point::new(2.0, 0.5, 3.0)),
+            Mat4::perspective(1.0, 1.5, 0.1, 100.0),
+        ];
+        for m in &matrices {
+            let inverse = m.inverse().unwrap();
+            assert_mat_close(&(inverse * *m), &Mat4::identity());
+            assert_mat_close(&(*m * inverse), &Mat4::identity());
+        }
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse() {
+        let m = Mat4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [2.0, 4.0, 6.0, 8.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0, 0.0],
+        ]);
+        assert_eq!(m.determinant(), 0.0);
+        assert!(m.inverse().is_none());
+        assert!(Mat4::scaling(Point::new(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+
+    #[test]
+    fn quaternion_matches_axis_angle_rotation() {
+        let axes = [
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(1.0, 2.0, -3.0),
+        ];
+        for axis in &axes {
+            for &angle in &[0.0, 0.3, FRAC_PI_2, 2.5, -1.2] {
+                assert_mat_close(
+                    &Mat4::from(Quat::from_axis_angle(*axis, angle)),
+                    &Mat4::rotation(*axis, angle),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rotation_is_right_handed() {
+        let m = Mat4::rotation(Point::new(0.0, 0.0, 1.0), FRAC_PI_2);
+        assert_point_close(
+            m.transform_vector(Point::new(1.0, 0.0, 0.0)),
+            Point::new(0.0, 1.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn look_at_matches_glu() {
+        // gluLookAt(5, 0, 0, 0, 0, 0, 0, 1, 0), worked by hand.
+        let m = Mat4::look_at(
+            Point::new(5.0, 0.0, 0.0),
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        );
+        let expected = Mat4::new([
+            [0.0, 0.0, -1.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, -5.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        assert_mat_close(&m, &expected);
+        assert_point_close(
+            m.transform_point(Point::new(0.0, 0.0, 0.0)),
+            Point::new(0.0, 0.0, -5.0),
+        );
+
+        // Looking down -z already only translates.
+        let m = Mat4::look_at(
+            Point::new(1.0, 2.0, 3.0),
+            Point::new(1.0, 2.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+        );
+        assert_mat_close(&m, &Mat4::translation(Point::new(-1.0, -2.0, -3.0)));
+    }
+
+    #[test]
+    fn perspective_matches_glu() {
+        // gluPerspective(90, 2, 1, 3), worked by hand.
+        let m = Mat4::perspective(FRAC_PI_2, 2.0, 1.0, 3.0);
+        let expected = Mat4::new([
+            [0.5, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, -2.0, -3.0],
+            [0.0, 0.0, -1.0, 0.0],
+        ]);
+        assert_mat_close(&m, &expected);
+        // The near and far planes land on -1 and 1.
+        assert_point_close(
+            m.transform_point(Point::new(0.0, 0.0, -1.0)),
+            Point::new(0.0, 0.0, -1.0),
+        );
+        assert_point_close(
+            m.transform_point(Point::new(2.0, 1.0, -3.0)),
+            Point::new(1.0 / 3.0, 1.0 / 3.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn only_points_are_translated() {
+        let m =
+            Mat4::translation(Point::new(1.0, 2.0, 3.0)) * Mat4::scaling(Point::new(2.0, 2.0, 2.0));
+        let v = Point::new(1.0, -1.0, 0.5);
+        assert_point_close(m.transform_point(v), Point::new(3.0, 0.0, 4.0));
+        assert_point_close(m.transform_vector(v), Point::new(2.0, -2.0, 1.0));
+    }
+}
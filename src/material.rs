@@ -1,106 +1,305 @@
+use std::f64::consts::PI;
+
+use crate::frame::Frame;
+use crate::ggx;
 use crate::hittable::HitRecord;
+use crate::pdf::Pdf;
 use crate::pixel::Pixel;
 use crate::point::Point;
+use crate::principled::Principled;
 use crate::ray::Ray;
+use crate::spectrum::Dispersion;
+use crate::texture::Texture;
 use rand::Rng;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum Material {
-    Lambertian { albedo: Pixel },
-    Metal { albedo: Pixel, fuzz: f64 },
-    Dielectric { ref_idx: f64 },
+    Lambertian {
+        albedo: Texture,
+    },
+    Metal {
+        albedo: Texture,
+        fuzz: f64,
+    },
+    /// Glass that absorbs `absorption` of each channel per unit distance
+    /// inside it, frosted by microfacets when `roughness` is above 0. Any
+    /// `dispersion` only shows in spectral renders.
+    Dielectric {
+        ref_idx: f64,
+        absorption: Pixel,
+        roughness: f64,
+        dispersion: Option<Dispersion>,
+    },
+    /// Scatters equally in every direction, for participating media.
+    Isotropic {
+        albedo: Texture,
+    },
+    /// Emits light from both sides and scatters none.
+    DiffuseLight {
+        emit: Texture,
+    },
+    Principled(Principled),
 }
 
 impl Default for Material {
     fn default() -> Material {
         Material::Lambertian {
-            albedo: Pixel::new(0.0, 0.0, 0.0),
+            albedo: Texture::default(),
         }
     }
 }
 
+/// A direction picked by `Material::scatter`.
+pub struct ScatterSample {
+    pub ray: Ray,
+    /// The fraction of the light arriving along `ray` that is scattered
+    /// back, which for sampled directions is `eval` over `pdf`.
+    pub attenuation: Pixel,
+    /// The density with which `ray` was picked, per unit solid angle, or
+    /// `None` for mirrors and glass. Lights aren't sampled for those, and
+    /// their `eval` and `pdf` are zero.
+    pub pdf: Option<f64>,
+    /// Whether `ray` passed through the surface into or out of the
+    /// material's `medium`.
+    pub crossed: bool,
+}
+
+/// The inside of a dielectric, which a ray travels through between entering
+/// and leaving it.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Medium {
+    pub ref_idx: f64,
+    pub absorption: Pixel,
+}
+
+impl Medium {
+    /// The fraction of light left after `distance` through the medium, by
+    /// the Beer-Lambert law.
+    pub fn transmittance(&self, distance: f64) -> Pixel {
+        let channel = |absorption: f64| {
+            if absorption > 0.0 {
+                (-absorption * distance).exp()
+            } else {
+                1.0
+            }
+        };
+        Pixel::new(
+            channel(self.absorption.r),
+            channel(self.absorption.g),
+            channel(self.absorption.b),
+        )
+    }
+}
+
 impl Material {
+    /// `media` are the dielectrics `ray` is inside, innermost last.
     pub fn scatter(
-        self,
+        &self,
         ray: &Ray,
         hit_record: &HitRecord,
-        attenuation: &mut Pixel,
-        scattered: &mut Ray,
-    ) -> bool {
+        media: &[Medium],
+    ) -> Option<ScatterSample> {
         match self {
-            Material::Lambertian { albedo } => {
-                Self::scatter_lambertian(&albedo, ray, hit_record, attenuation, scattered)
+            Material::Lambertian { .. } | Material::Isotropic { .. } | Material::Principled(_) => {
+                self.scatter_sampled(ray, hit_record)
             }
-            Material::Metal { albedo, fuzz } => {
-                Self::scatter_metal(&albedo, fuzz, ray, hit_record, attenuation, scattered)
+            Material::Metal { albedo, fuzz } => Self::scatter_metal(albedo, *fuzz, ray, hit_record),
+            Material::Dielectric { roughness, .. } => {
+                self.scatter_dielectric(*roughness, media, ray, hit_record)
             }
-            Material::Dielectric { ref_idx } => {
-                Self::scatter_dielectric(ref_idx, ray, hit_record, attenuation, scattered)
+            Material::DiffuseLight { .. } => None,
+        }
+    }
+
+    /// The BSDF times the cosine of `direction` to the normal: the fraction
+    /// of the light arriving from `direction`, per unit solid angle, that is
+    /// scattered back along `ray`.
+    pub fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: &Point) -> Pixel {
+        let HitRecord { u, v, point, .. } = hit_record;
+        match self {
+            Material::Lambertian { albedo } => {
+                let cosine = hit_record.normal.dot(&direction.unit_vector());
+                albedo.value(*u, *v, point) * (cosine.max(0.0) / PI)
             }
+            Material::Isotropic { albedo } => albedo.value(*u, *v, point) * (1.0 / (4.0 * PI)),
+            Material::Principled(principled) => principled.eval(
+                principled.base_color.value(*u, *v, point),
+                hit_record.normal,
+                -ray.direction.unit_vector(),
+                direction.unit_vector(),
+            ),
+            _ => Pixel::new(0.0, 0.0, 0.0),
         }
     }
 
-    fn scatter_lambertian(
-        albedo: &Pixel,
-        _ray: &Ray,
-        hit_record: &HitRecord,
-        attenuation: &mut Pixel,
-        scattered: &mut Ray,
-    ) -> bool {
-        let scatter_direction = hit_record.normal + Point::random_unit_vector();
-        *scattered = Ray::new(hit_record.point, scatter_direction);
-        *attenuation = *albedo;
-        true
+    /// The density, per unit solid angle, with which `scatter` picks
+    /// `direction`.
+    pub fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: &Point) -> f64 {
+        match self.sampling_pdf(ray, hit_record) {
+            Some(pdf) => pdf.value(direction),
+            None => 0.0,
+        }
+    }
+
+    /// What a ray of `wavelength` passing into the material travels
+    /// through.
+    pub fn medium(&self, wavelength: Option<f64>) -> Option<Medium> {
+        match self {
+            Material::Dielectric {
+                ref_idx,
+                absorption,
+                dispersion,
+                ..
+            } => Some(Medium {
+                ref_idx: match (dispersion, wavelength) {
+                    (Some(dispersion), Some(wavelength)) => {
+                        dispersion.ref_idx(*ref_idx, wavelength)
+                    }
+                    _ => *ref_idx,
+                },
+                absorption: *absorption,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The radiance given off at a point, black for all but lights.
+    pub fn emitted(&self, u: f64, v: f64, p: &Point) -> Pixel {
+        match self {
+            Material::DiffuseLight { emit } => emit.value(u, v, p),
+            _ => Pixel::new(0.0, 0.0, 0.0),
+        }
+    }
+
+    /// The distribution `scatter` draws from, for materials that don't pick
+    /// a single direction.
+    /// Whether scattering draws directions from a density, which lights
+    /// sampled directly can be weighted against.
+    pub fn has_pdf(&self, ray: &Ray, hit_record: &HitRecord) -> bool {
+        self.sampling_pdf(ray, hit_record).is_some()
+    }
+
+    fn sampling_pdf(&self, ray: &Ray, hit_record: &HitRecord) -> Option<Pdf<'static>> {
+        match self {
+            Material::Lambertian { .. } => Some(Pdf::cosine(hit_record.normal)),
+            Material::Isotropic { .. } => Some(Pdf::Uniform),
+            Material::Principled(principled) => {
+                Some(principled.sampling_pdf(hit_record.normal, -ray.direction))
+            }
+            _ => None,
+        }
+    }
+
+    fn scatter_sampled(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterSample> {
+        let pdf = self.sampling_pdf(ray, hit_record)?;
+        let direction = pdf.generate();
+        let value = pdf.value(&direction);
+        if value <= 0.0 {
+            return None;
+        }
+        Some(ScatterSample {
+            ray: ray.scattered(hit_record.point, direction),
+            attenuation: self.eval(ray, hit_record, &direction) * (1.0 / value),
+            pdf: Some(value),
+            crossed: false,
+        })
     }
 
     fn scatter_metal(
-        albedo: &Pixel,
+        albedo: &Texture,
         fuzz: f64,
         ray: &Ray,
         hit_record: &HitRecord,
-        attenuation: &mut Pixel,
-        scattered: &mut Ray,
-    ) -> bool {
+    ) -> Option<ScatterSample> {
         let reflected = ray.direction.unit_vector().reflect(&hit_record.normal);
-        *scattered = Ray::new(
+        let scattered = ray.scattered(
             hit_record.point,
             reflected + Point::random_in_unit_sphere() * fuzz,
         );
-        *attenuation = *albedo;
-        scattered.direction.dot(&hit_record.normal) > 0.0
+        if scattered.direction.dot(&hit_record.normal) <= 0.0 {
+            return None;
+        }
+        Some(ScatterSample {
+            ray: scattered,
+            attenuation: albedo.value(hit_record.u, hit_record.v, &hit_record.point),
+            pdf: None,
+            crossed: false,
+        })
     }
 
     fn scatter_dielectric(
-        ref_idx: f64,
+        &self,
+        roughness: f64,
+        media: &[Medium],
         ray_in: &Ray,
         hit_record: &HitRecord,
-        attenuation: &mut Pixel,
-        scattered: &mut Ray,
-    ) -> bool {
-        *attenuation = Pixel::new(1.0, 1.0, 1.0);
-        let refraction_ratio = if hit_record.front_face {
-            1.0 / ref_idx
-        } else {
-            ref_idx
+    ) -> Option<ScatterSample> {
+        let medium = self.medium(ray_in.wavelength)?;
+        let ref_idx = medium.ref_idx;
+        let outside = |media: &[Medium]| media.last().map_or(1.0, |medium| medium.ref_idx);
+        let (from, to) = match media.split_last() {
+            _ if hit_record.front_face => (outside(media), ref_idx),
+            Some((inside, rest)) if *inside == medium => (ref_idx, outside(rest)),
+            // Leaving a dielectric from inside another one, which the ray
+            // stays in.
+            Some((inside, _)) => (inside.ref_idx, inside.ref_idx),
+            None => (ref_idx, 1.0),
         };
         let unit_direction = ray_in.direction.unit_vector();
+        if from == to {
+            return Some(ScatterSample {
+                ray: ray_in.scattered(hit_record.point, unit_direction),
+                attenuation: Pixel::new(1.0, 1.0, 1.0),
+                pdf: None,
+                crossed: true,
+            });
+        }
+        let refraction_ratio = from / to;
+
+        // Rough glass reflects and refracts about a microfacet normal, picked
+        // from those facing the ray, rather than about the surface normal.
+        let alpha = ggx::alpha(roughness);
+        let frame = Frame::new(Point::default(), hit_record.normal);
+        let wo = frame.vector_to_local(-unit_direction);
+        let mut rng = rand::thread_rng();
+        let normal = if roughness > 0.0 {
+            let (u1, u2) = (rng.gen_range(0.0, 1.0), rng.gen_range(0.0, 1.0));
+            frame.vector_to_world(ggx::sample_visible_normal(&wo, alpha, u1, u2))
+        } else {
+            hit_record.normal
+        };
 
-        let cos_theta = -unit_direction.dot(&hit_record.normal).min(1.0);
+        let cos_theta = (-unit_direction).dot(&normal).min(1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
 
         let cannot_refract = refraction_ratio * sin_theta > 1.0;
 
-        let mut rng = rand::thread_rng();
         let direction = if cannot_refract
             || Material::reflectance(cos_theta, refraction_ratio) > rng.gen_range(0.0, 1.0)
         {
-            unit_direction.reflect(&hit_record.normal)
+            unit_direction.reflect(&normal)
         } else {
-            unit_direction.refract(&hit_record.normal, refraction_ratio)
+            unit_direction.refract(&normal, refraction_ratio)
         };
+        // A microfacet can send the ray back the way it came.
+        let crossed = direction.dot(&hit_record.normal) < 0.0;
+        if roughness > 0.0 && crossed == (direction.dot(&normal) > 0.0) {
+            return None;
+        }
 
-        *scattered = Ray::new(hit_record.point, direction);
-        true
+        // Of the microfacets facing the ray, those shadowed from `direction`.
+        let attenuation = if roughness > 0.0 {
+            let wi = frame.vector_to_local(direction);
+            ggx::g2(&wo, &wi, alpha) / ggx::g1(&wo, alpha)
+        } else {
+            1.0
+        };
+        Some(ScatterSample {
+            ray: ray_in.scattered(hit_record.point, direction),
+            attenuation: Pixel::new(attenuation, attenuation, attenuation),
+            pdf: None,
+            crossed,
+        })
     }
 
     fn reflectance(cosine: f64, ref_idx: f64) -> f64 {
@@ -110,3 +309,108 @@ impl Material {
         r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glass(ref_idx: f64) -> Material {
+        Material::Dielectric {
+            ref_idx,
+            absorption: Pixel::new(0.0, 0.0, 0.0),
+            roughness: 0.0,
+            dispersion: None,
+        }
+    }
+
+    fn medium(material: &Material) -> Medium {
+        material.medium(None).unwrap()
+    }
+
+    /// A ray meeting the plane y = 0 from above, 0.8 of the way to grazing,
+    /// and the hit it makes there. `front_face` says whether the ray is
+    /// passing into the material or out of it.
+    fn oblique_hit(front_face: bool) -> (Ray, HitRecord) {
+        let ray = Ray::new(Point::new(-0.8, 0.6, 0.0), Point::new(0.8, -0.6, 0.0), 0.0);
+        let hit = HitRecord {
+            t: 1.0,
+            normal: Point::new(0.0, 1.0, 0.0),
+            front_face,
+            ..HitRecord::default()
+        };
+        (ray, hit)
+    }
+
+    /// Of `n` scatters, how many cross the surface, and the sine of the
+    /// angle the last crossing ray makes with the normal.
+    fn crossings(material: &Material, front_face: bool, media: &[Medium]) -> (usize, f64) {
+        let (ray, hit) = oblique_hit(front_face);
+        let mut count = 0;
+        let mut sine = 0.0;
+        for _ in 0..200 {
+            let sample = material.scatter(&ray, &hit, media).unwrap();
+            if sample.crossed {
+                count += 1;
+                sine = sample.ray.direction.unit_vector().x;
+            }
+        }
+        (count, sine)
+    }
+
+    #[test]
+    fn beer_lambert_transmittance() {
+        let medium = Medium {
+            ref_idx: 1.5,
+            absorption: Pixel::new(1.0, 0.0, 2.0),
+        };
+        let t = medium.transmittance(0.5);
+        assert!((t.r - (-0.5_f64).exp()).abs() < 1e-12);
+        assert_eq!(t.g, 1.0);
+        assert!((t.b - (-1.0_f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn leaving_glass_into_air_reflects_totally() {
+        let glass = glass(1.5);
+        let (count, _) = crossings(&glass, false, &[medium(&glass)]);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn leaving_glass_into_water_refracts() {
+        // 0.8 is past the critical sine into air, 1 / 1.5, but not into
+        // water, 1.33 / 1.5.
+        let (water, glass) = (glass(1.33), glass(1.5));
+        let media = [medium(&water), medium(&glass)];
+        let (count, sine) = crossings(&glass, false, &media);
+        assert!(count > 100, "{}", count);
+        assert!((sine - 0.8 * 1.5 / 1.33).abs() < 1e-9);
+    }
+
+    #[test]
+    fn entering_uses_the_surrounding_index() {
+        let (water, glass) = (glass(1.33), glass(1.5));
+        let (count, sine) = crossings(&glass, true, &[medium(&water)]);
+        assert!(count > 150, "{}", count);
+        assert!((sine - 0.8 * 1.33 / 1.5).abs() < 1e-9);
+        let (_, sine) = crossings(&glass, true, &[]);
+        assert!((sine - 0.8 / 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn matching_indices_pass_straight_through() {
+        let glass = glass(1.5);
+        let (count, sine) = crossings(&glass, true, &[medium(&glass)]);
+        assert_eq!((count, sine), (200, 0.8));
+    }
+
+    #[test]
+    fn leaving_an_enclosing_dielectric_from_inside_another_is_invisible() {
+        // Overlapping volumes: the ray is in water, then enters the glass,
+        // and meets the back of the water while still in the glass.
+        let (water, glass) = (glass(1.33), glass(1.5));
+        let media = [medium(&water), medium(&glass)];
+        let (count, sine) = crossings(&water, false, &media);
+        assert_eq!((count, sine), (200, 0.8));
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::bvh::BvhNode;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+use crate::triangle;
+
+/// Indices of one triangle's attributes in a `MeshData`'s buffers.
+#[derive(Clone, Copy, Debug)]
+pub struct Face {
+    pub positions: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+    pub uvs: Option<[usize; 3]>,
+}
+
+/// Vertex buffers shared by every triangle of a mesh.
+#[derive(Default)]
+pub struct MeshData {
+    pub positions: Vec<Point>,
+    pub normals: Vec<Point>,
+    pub uvs: Vec<(f64, f64)>,
+    pub faces: Vec<Face>,
+    pub material: Material,
+}
+
+impl MeshData {
+    fn vertices(&self, face: &Face) -> [Point; 3] {
+        let p = face.positions;
+        [
+            self.positions[p[0]],
+            self.positions[p[1]],
+            self.positions[p[2]],
+        ]
+    }
+}
+
+/// A triangle mesh with its own BVH over its faces.
+pub struct TriangleMesh {
+    bvh: BvhNode,
+}
+
+impl TriangleMesh {
+    /// Panics if the mesh has no faces or a face refers past the end of a
+    /// buffer.
+    pub fn new(data: MeshData) -> Self {
+        let data = Arc::new(data);
+        let triangles = (0..data.faces.len())
+            .map(|face| {
+                Box::new(MeshTriangle {
+                    mesh: data.clone(),
+                    face,
+                }) as Box<dyn Hittable + Send + Sync>
+            })
+            .collect();
+        Self {
+            bvh: BvhNode::new(triangles),
+        }
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, rec: &mut HitRecord) -> bool {
+        self.bvh.hit(ray, t_min, t_max, rec)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bvh.bounding_box()
+    }
+}
+
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    face: usize,
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        let mesh = &self.mesh;
+        let face = &mesh.faces[self.face];
+        let vertices = mesh.vertices(face);
+        match triangle::intersect(&vertices, ray, t_min, t_max) {
+            Some((t, b1, b2)) => {
+                let normals = face
+                    .normals
+                    .map(|n| [mesh.normals[n[0]], mesh.normals[n[1]], mesh.normals[n[2]]]);
+                let uvs = face
+                    .uvs
+                    .map(|uv| [mesh.uvs[uv[0]], mesh.uvs[uv[1]], mesh.uvs[uv[2]]]);
+                triangle::set_hit(
+                    hit,
+                    ray,
+                    t,
+                    (b1, b2),
+                    &vertices,
+                    normals.as_ref(),
+                    uvs.as_ref(),
+                );
+                *hit.material = mesh.material.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(triangle::bounding_box(
+            &self.mesh.vertices(&self.mesh.faces[self.face]),
+        ))
+    }
+}
@@ -0,0 +1,86 @@
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+use crate::sphere;
+
+/// A sphere moving in a straight line from `center0` at `time0` to
+/// `center1` at `time1`. Rays at other times see it resting at the nearer
+/// end, so it never leaves its bounding box.
+pub struct MovingSphere {
+    pub center0: Point,
+    pub center1: Point,
+    pub time0: f64,
+    pub time1: f64,
+    pub radius: f64,
+    pub material: Box<Material>,
+}
+
+impl MovingSphere {
+    pub fn new(
+        (center0, time0): (Point, f64),
+        (center1, time1): (Point, f64),
+        radius: f64,
+        material: Box<Material>,
+    ) -> Self {
+        MovingSphere {
+            center0,
+            center1,
+            time0,
+            time1,
+            radius,
+            material,
+        }
+    }
+
+    pub fn center(&self, time: f64) -> Point {
+        if self.time1 == self.time0 {
+            return self.center0;
+        }
+        let f = ((time - self.time0) / (self.time1 - self.time0)).clamp(0.0, 1.0);
+        self.center0 + (self.center1 - self.center0) * f
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        let center = self.center(ray.time);
+        let oc = ray.origin - center;
+        let a = ray.direction.length_squared();
+        let half_b = oc.dot(&ray.direction);
+        let c = oc.length_squared() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return false;
+        }
+
+        let root = discriminant.sqrt();
+        let mut t = (-half_b - root) / a;
+        if t <= t_min || t >= t_max {
+            t = (-half_b + root) / a;
+            if t <= t_min || t >= t_max {
+                return false;
+            }
+        }
+
+        hit.t = t;
+        hit.point = ray.at(t);
+        let outward_normal = (hit.point - center) / self.radius;
+        hit.set_face_normal(ray, outward_normal);
+        let (u, v) = sphere::uv(&outward_normal);
+        hit.u = u;
+        hit.v = v;
+        hit.material = self.material.clone();
+        true
+    }
+
+    /// The bounds over all time, since the sphere stops at either end.
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r = Point::new(self.radius, self.radius, self.radius);
+        let start = Aabb::new(self.center0 - r, self.center0 + r);
+        let end = Aabb::new(self.center1 - r, self.center1 + r);
+        Some(start.union(&end))
+    }
+}
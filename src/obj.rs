@@ -0,0 +1,626 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::hittable_list::HittableList;
+use crate::material::Material;
+use crate::mesh::{Face, MeshData, TriangleMesh};
+use crate::pixel::Pixel;
+use crate::point::Point;
+use crate::principled::Principled;
+use crate::texture::{ImageTexture, Texture};
+
+#[derive(Debug)]
+pub struct ObjError {
+    pub path: PathBuf,
+    /// 1-based line number, or 0 if the error isn't tied to a line.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.path.display(), self.message)
+        } else {
+            write!(f, "{}:{}: {}", self.path.display(), self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+/// Load a Wavefront OBJ file as one `TriangleMesh` per group and material.
+/// Materials come from any `mtllib` the file names; faces without a
+/// `usemtl` get `default_material`.
+pub fn load(path: &Path, default_material: Material) -> Result<HittableList, ObjError> {
+    parse(&read(path)?, path, default_material)
+}
+
+/// Parse the OBJ file `source`. `path` is used when reporting errors and to
+/// find the MTL files it names.
+fn parse(source: &str, path: &Path, default_material: Material) -> Result<HittableList, ObjError> {
+    let mut parser = ObjParser::new(path, default_material);
+    for (index, line) in source.lines().enumerate() {
+        parser
+            .parse_line(line)
+            .map_err(|message| error(path, index + 1, message))?;
+    }
+
+    let mut list = HittableList::default();
+    for mesh in parser.into_meshes() {
+        list.add(Box::new(TriangleMesh::new(mesh)));
+    }
+    if list.objects.is_empty() {
+        return Err(error(path, 0, "no faces".to_string()));
+    }
+    Ok(list)
+}
+
+/// Load the materials in a Wavefront MTL file, mapped onto the closest
+/// `Material`: emissive materials become `DiffuseLight`, transparent ones
+/// `Dielectric`, tinted by their transmission filter and frosted by any PBR
+/// roughness, ones with the PBR extension's roughness or metalness
+/// `Principled`, ones whose specular colour outshines their diffuse colour
+/// `Metal` and the rest `Lambertian`, textured by any diffuse map.
+pub fn load_mtl(path: &Path) -> Result<HashMap<String, Material>, ObjError> {
+    parse_mtl(&read(path)?, path)
+}
+
+/// Parse the MTL file `source`. `path` is used when reporting errors and to
+/// find the texture files it names.
+fn parse_mtl(source: &str, path: &Path) -> Result<HashMap<String, Material>, ObjError> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlDesc)> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let fail = |message: String| error(path, index + 1, message);
+        let mut words = statement(line);
+        let keyword = match words.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let args = words.collect::<Vec<&str>>();
+
+        if keyword == "newmtl" {
+            let name = args.join(" ");
+            if name.is_empty() {
+                return Err(fail("`newmtl` needs a name".to_string()));
+            }
+            if let Some((name, desc)) = current.take() {
+                materials.insert(name, desc.material());
+            }
+            current = Some((name, MtlDesc::default()));
+            continue;
+        }
+
+        let desc = match (&mut current, keyword) {
+            (Some((_, desc)), _) => desc,
+            (None, "Kd")
+            | (None, "Ks")
+            | (None, "Ke")
+            | (None, "Ns")
+            | (None, "Ni")
+            | (None, "d")
+            | (None, "Tr")
+            | (None, "Tf")
+            | (None, "Pr")
+            | (None, "Pm")
+            | (None, "Pc")
+            | (None, "Pcr")
+            | (None, "map_Kd") => {
+                return Err(fail(format!("`{}` before any `newmtl`", keyword)));
+            }
+            (None, _) => continue,
+        };
+        match keyword {
+            "Kd" => desc.kd = color(&args).map_err(fail)?,
+            "Ks" => desc.ks = color(&args).map_err(fail)?,
+            "Ke" => desc.ke = color(&args).map_err(fail)?,
+            "Ns" => desc.ns = single(&args, keyword).map_err(fail)?,
+            "Ni" => {
+                desc.ni = single(&args, keyword).map_err(fail)?;
+                if desc.ni <= 0.0 {
+                    return Err(fail("`Ni` must be positive".to_string()));
+                }
+            }
+            "d" => desc.d = single(&args, keyword).map_err(fail)?,
+            "Tr" => desc.d = 1.0 - single(&args, keyword).map_err(fail)?,
+            "Tf" => desc.tf = color(&args).map_err(fail)?,
+            "Pr" => desc.pr = Some(single(&args, keyword).map_err(fail)?),
+            "Pm" => desc.pm = Some(single(&args, keyword).map_err(fail)?),
+            "Pc" => desc.pc = Some(single(&args, keyword).map_err(fail)?),
+            "Pcr" => desc.pcr = Some(single(&args, keyword).map_err(fail)?),
+            "map_Kd" => {
+                // Options such as `-s` come first; the file name is last.
+                let file = args
+                    .last()
+                    .ok_or_else(|| fail("`map_Kd` needs a file".to_string()))?;
+                let file = path.parent().unwrap_or_else(|| Path::new("")).join(file);
+                let image = ImageTexture::open(&file)
+                    .map_err(|e| fail(format!("{}: {}", file.display(), e)))?;
+                desc.map_kd = Some(Arc::new(image));
+            }
+            _ => {}
+        }
+    }
+    if let Some((name, desc)) = current {
+        materials.insert(name, desc.material());
+    }
+    Ok(materials)
+}
+
+fn read(path: &Path) -> Result<String, ObjError> {
+    fs::read_to_string(path).map_err(|e| error(path, 0, e.to_string()))
+}
+
+fn error(path: &Path, line: usize, message: String) -> ObjError {
+    ObjError {
+        path: path.to_path_buf(),
+        line,
+        message,
+    }
+}
+
+/// The words of a line with any `#` comment removed.
+fn statement(line: &str) -> std::str::SplitWhitespace<'_> {
+    line.split('#').next().unwrap_or("").split_whitespace()
+}
+
+fn numbers(args: &[&str]) -> Result<Vec<f64>, String> {
+    args.iter()
+        .map(|a| match a.parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(n),
+            _ => Err(format!("invalid number `{}`", a)),
+        })
+        .collect()
+}
+
+fn single(args: &[&str], keyword: &str) -> Result<f64, String> {
+    match numbers(args)?[..] {
+        [n] => Ok(n),
+        _ => Err(format!("`{}` takes one number", keyword)),
+    }
+}
+
+fn color(args: &[&str]) -> Result<Pixel, String> {
+    match numbers(args)?[..] {
+        [r, g, b] => Ok(Pixel::new(r, g, b)),
+        [v] => Ok(Pixel::new(v, v, v)),
+        _ => Err("expected an r g b colour".to_string()),
+    }
+}
+
+struct MtlDesc {
+    kd: Pixel,
+    ks: Pixel,
+    ke: Pixel,
+    ns: f64,
+    ni: f64,
+    d: f64,
+    /// The colour white light takes on through a unit of a transparent
+    /// material.
+    tf: Pixel,
+    /// Roughness, metalness, clear coat and clear coat roughness from the
+    /// PBR extension.
+    pr: Option<f64>,
+    pm: Option<f64>,
+    pc: Option<f64>,
+    pcr: Option<f64>,
+    map_kd: Option<Arc<ImageTexture>>,
+}
+
+impl Default for MtlDesc {
+    fn default() -> Self {
+        Self {
+            kd: Pixel::new(0.8, 0.8, 0.8),
+            ks: Pixel::new(0.0, 0.0, 0.0),
+            ke: Pixel::new(0.0, 0.0, 0.0),
+            ns: 0.0,
+            ni: 1.5,
+            d: 1.0,
+            tf: Pixel::new(1.0, 1.0, 1.0),
+            pr: None,
+            pm: None,
+            pc: None,
+            pcr: None,
+            map_kd: None,
+        }
+    }
+}
+
+impl MtlDesc {
+    fn material(&self) -> Material {
+        let brightest = |p: &Pixel| p.r.max(p.g).max(p.b);
+        if brightest(&self.ke) > 0.0 {
+            Material::DiffuseLight {
+                emit: self.ke.into(),
+            }
+        } else if self.d < 1.0 {
+            let absorption = |t: f64| -t.clamp(1e-4, 1.0).ln();
+            Material::Dielectric {
+                ref_idx: self.ni,
+                absorption: Pixel::new(
+                    absorption(self.tf.r),
+                    absorption(self.tf.g),
+                    absorption(self.tf.b),
+                ),
+                roughness: self.pr.map_or(0.0, |r| r.clamp(0.0, 1.0)),
+                dispersion: None,
+            }
+        } else if self.pr.is_some() || self.pm.is_some() {
+            let defaults = Principled::default();
+            let fraction =
+                |value: Option<f64>, default: f64| value.map_or(default, |v| v.clamp(0.0, 1.0));
+            Material::Principled(Principled {
+                base_color: self.diffuse(),
+                roughness: fraction(self.pr, defaults.roughness),
+                metallic: fraction(self.pm, defaults.metallic),
+                clearcoat: fraction(self.pc, defaults.clearcoat),
+                clearcoat_roughness: fraction(self.pcr, defaults.clearcoat_roughness),
+                ..defaults
+            })
+        } else if brightest(&self.ks) > brightest(&self.kd) {
+            // Map the Phong exponent to a roughness, as Blender does.
+            let fuzz = (2.0 / (self.ns.max(0.0) + 2.0)).sqrt();
+            Material::Metal {
+                albedo: self.ks.into(),
+                fuzz: fuzz.min(1.0),
+            }
+        } else {
+            Material::Lambertian {
+                albedo: self.diffuse(),
+            }
+        }
+    }
+
+    fn diffuse(&self) -> Texture {
+        // A diffuse map replaces the diffuse colour rather than tinting it.
+        match &self.map_kd {
+            Some(image) => Texture::Image(image.clone()),
+            None => self.kd.into(),
+        }
+    }
+}
+
+/// An OBJ group name and the material its faces use.
+type GroupKey = (String, Option<String>);
+
+/// Position, texture coordinate and normal indices of a face corner.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+struct ObjParser<'a> {
+    path: &'a Path,
+    positions: Vec<Point>,
+    normals: Vec<Point>,
+    uvs: Vec<(f64, f64)>,
+    materials: HashMap<String, Material>,
+    default_material: Material,
+    group: String,
+    material: Option<String>,
+    /// Faces, indexing the whole file's buffers, keyed by group and material.
+    faces: Vec<(GroupKey, Vec<Face>)>,
+    /// Where each group and material's faces are in `faces`.
+    groups: HashMap<GroupKey, usize>,
+}
+
+impl<'a> ObjParser<'a> {
+    fn new(path: &'a Path, default_material: Material) -> Self {
+        Self {
+            path,
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            materials: HashMap::new(),
+            default_material,
+            group: String::new(),
+            material: None,
+            faces: Vec::new(),
+            groups: HashMap::new(),
+        }
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<(), String> {
+        let mut words = statement(line);
+        let keyword = match words.next() {
+            Some(keyword) => keyword,
+            None => return Ok(()),
+        };
+        let args = words.collect::<Vec<&str>>();
+
+        match keyword {
+            "v" => match numbers(&args)?[..] {
+                [x, y, z] | [x, y, z, _] => self.positions.push(Point::new(x, y, z)),
+                _ => return Err("`v` needs x y z".to_string()),
+            },
+            "vn" => match numbers(&args)?[..] {
+                [x, y, z] => self.normals.push(Point::new(x, y, z)),
+                _ => return Err("`vn` needs x y z".to_string()),
+            },
+            "vt" => match numbers(&args)?[..] {
+                [u] => self.uvs.push((u, 0.0)),
+                [u, v] | [u, v, _] => self.uvs.push((u, v)),
+                _ => return Err("`vt` needs u [v [w]]".to_string()),
+            },
+            "f" => self.face(&args)?,
+            "g" | "o" => self.group = args.join(" "),
+            "usemtl" => {
+                let name = args.join(" ");
+                if !self.materials.contains_key(&name) {
+                    return Err(format!("unknown material `{}`", name));
+                }
+                self.material = Some(name);
+            }
+            "mtllib" => {
+                for file in args {
+                    let path = self.path.with_file_name(file);
+                    let materials = load_mtl(&path).map_err(|e| e.to_string())?;
+                    self.materials.extend(materials);
+                }
+            }
+            // Smoothing groups, lines, points and free-form geometry aren't
+            // supported and are skipped.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Parse a polygon and fan triangulate it.
+    fn face(&mut self, args: &[&str]) -> Result<(), String> {
+        if args.len() < 3 {
+            return Err(format!(
+                "face needs at least 3 vertices, found {}",
+                args.len()
+            ));
+        }
+
+        let mut corners = Vec::with_capacity(args.len());
+        for corner in args {
+            let mut parts = corner.split('/');
+            let position = index(parts.next(), self.positions.len(), "vertex")?
+                .ok_or_else(|| format!("face vertex `{}` has no position", corner))?;
+            let uv = index(parts.next(), self.uvs.len(), "texture coordinate")?;
+            let normal = index(parts.next(), self.normals.len(), "normal")?;
+            corners.push((position, uv, normal));
+        }
+
+        let key = (self.group.clone(), self.material.clone());
+        let faces = &mut self.faces;
+        let i = *self.groups.entry(key.clone()).or_insert_with(|| {
+            faces.push((key, Vec::new()));
+            faces.len() - 1
+        });
+        let faces = &mut self.faces[i].1;
+
+        for i in 1..corners.len() - 1 {
+            let tri = [corners[0], corners[i], corners[i + 1]];
+            let all = |f: fn(&Corner) -> Option<usize>| match (f(&tri[0]), f(&tri[1]), f(&tri[2])) {
+                (Some(a), Some(b), Some(c)) => Some([a, b, c]),
+                _ => None,
+            };
+            faces.push(Face {
+                positions: [tri[0].0, tri[1].0, tri[2].0],
+                uvs: all(|c| c.1),
+                normals: all(|c| c.2),
+            });
+        }
+        Ok(())
+    }
+
+    /// Split the file into one mesh per group and material, each with only
+    /// the vertices it uses.
+    fn into_meshes(self) -> Vec<MeshData> {
+        let mut meshes = Vec::new();
+        for ((_, material), faces) in &self.faces {
+            let mut mesh = MeshData {
+                material: match material {
+                    Some(name) => self.materials[name].clone(),
+                    None => self.default_material.clone(),
+                },
+                ..MeshData::default()
+            };
+            let mut positions = HashMap::new();
+            let mut normals = HashMap::new();
+            let mut uvs = HashMap::new();
+            for face in faces {
+                mesh.faces.push(Face {
+                    positions: remap(face.positions, &mut positions),
+                    normals: face.normals.map(|n| remap(n, &mut normals)),
+                    uvs: face.uvs.map(|uv| remap(uv, &mut uvs)),
+                });
+            }
+            mesh.positions = ordered(positions, &self.positions);
+            mesh.normals = ordered(normals, &self.normals);
+            mesh.uvs = ordered(uvs, &self.uvs);
+            meshes.push(mesh);
+        }
+        meshes
+    }
+}
+
+/// Resolve an OBJ index, which is 1-based or negative to count back from the
+/// latest element. Returns `None` for an empty or missing field.
+fn index(field: Option<&str>, len: usize, what: &str) -> Result<Option<usize>, String> {
+    let field = match field {
+        Some(f) if !f.is_empty() => f,
+        _ => return Ok(None),
+    };
+    let i = field
+        .parse::<i64>()
+        .map_err(|_| format!("invalid {} index `{}`", what, field))?;
+    let resolved = if i > 0 { i - 1 } else { len as i64 + i };
+    if i == 0 || resolved < 0 || resolved >= len as i64 {
+        return Err(format!(
+            "{} index {} out of range, there are {} so far",
+            what, i, len
+        ));
+    }
+    Ok(Some(resolved as usize))
+}
+
+/// Renumber global indices densely, in order of first use.
+fn remap(indices: [usize; 3], map: &mut HashMap<usize, usize>) -> [usize; 3] {
+    let mut out = [0; 3];
+    for (o, i) in out.iter_mut().zip(indices.iter()) {
+        let next = map.len();
+        *o = *map.entry(*i).or_insert(next);
+    }
+    out
+}
+
+fn ordered<T: Copy + Default>(map: HashMap<usize, usize>, values: &[T]) -> Vec<T> {
+    let mut out = vec![T::default(); map.len()];
+    for (global, local) in map {
+        out[local] = values[global];
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grey() -> Material {
+        Material::Lambertian {
+            albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+        }
+    }
+
+    fn parser(lines: &str) -> Result<ObjParser<'static>, String> {
+        let mut parser = ObjParser::new(Path::new("test.obj"), grey());
+        parser.materials.insert("red".to_string(), grey());
+        parser.materials.insert("blue".to_string(), grey());
+        for line in lines.lines() {
+            parser.parse_line(line)?;
+        }
+        Ok(parser)
+    }
+
+    const SQUARE: &str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 2 0\n";
+
+    fn faces(parser: &ObjParser) -> Vec<[usize; 3]> {
+        parser
+            .faces
+            .iter()
+            .flat_map(|(_, faces)| faces.iter().map(|f| f.positions))
+            .collect()
+    }
+
+    #[test]
+    fn fan_triangulates_polygons() {
+        let parser = parser(&format!("{}f 1 2 3 4 5\n", SQUARE)).unwrap();
+        assert_eq!(faces(&parser), vec![[0, 1, 2], [0, 2, 3], [0, 3, 4]]);
+    }
+
+    #[test]
+    fn negative_indices_count_back() {
+        let parser = parser(&format!("{}f -3 -2 -1\nv 5 5 5\nf -4 -1 1\n", SQUARE)).unwrap();
+        assert_eq!(faces(&parser), vec![[2, 3, 4], [2, 5, 0]]);
+        assert!(self::parser(&format!("{}f 1 2 -6\n", SQUARE)).is_err());
+        assert!(self::parser(&format!("{}f 0 1 2\n", SQUARE)).is_err());
+    }
+
+    #[test]
+    fn face_forms() {
+        let buffers = "vt 0 0\nvt 1 0\nvt 0 1\nvn 0 0 1\nvn 0 0 1\nvn 0 0 1\n";
+        let face = |corners: &str| {
+            let parser = parser(&format!("{}{}f {}\n", SQUARE, buffers, corners)).unwrap();
+            let face = &parser.faces[0].1[0];
+            (face.positions, face.uvs, face.normals)
+        };
+        assert_eq!(
+            face("1/1/1 2/2/2 3/3/3"),
+            ([0, 1, 2], Some([0, 1, 2]), Some([0, 1, 2]))
+        );
+        assert_eq!(face("1//3 2//2 3//1"), ([0, 1, 2], None, Some([2, 1, 0])));
+        assert_eq!(face("1/3 2/2 3/1"), ([0, 1, 2], Some([2, 1, 0]), None));
+        // A corner without a normal leaves the whole triangle without them.
+        assert_eq!(face("1/1/1 2/2 3/3/3"), ([0, 1, 2], Some([0, 1, 2]), None));
+    }
+
+    #[test]
+    fn groups_by_name_and_material() {
+        let parser = parser(&format!(
+            "{}g a\nf 1 2 3\nusemtl red\nf 1 3 4\ng b\nf 2 3 4\ng a\nusemtl blue\nf 1 2 4\nusemtl red\nf 3 4 5\n",
+            SQUARE
+        ))
+        .unwrap();
+        let keys = parser.faces.iter().map(|(k, f)| (k.clone(), f.len()));
+        let group = |name: &str, material: Option<&str>, count| {
+            ((name.to_string(), material.map(str::to_string)), count)
+        };
+        assert_eq!(
+            keys.collect::<Vec<_>>(),
+            vec![
+                group("a", None, 1),
+                group("a", Some("red"), 2),
+                group("b", Some("red"), 1),
+                group("a", Some("blue"), 1),
+            ]
+        );
+
+        // Each mesh keeps only the vertices it uses, renumbered from 0.
+        let meshes = parser.into_meshes();
+        assert_eq!(meshes.len(), 4);
+        assert_eq!(meshes[1].positions.len(), 4);
+        assert_eq!(meshes[1].faces[1].positions, [1, 2, 3]);
+        assert_eq!(meshes[2].positions.len(), 3);
+    }
+
+    #[test]
+    fn rejects_non_finite_numbers() {
+        for line in &["v nan 0 0", "v 0 inf 0", "vn 0 0 -inf", "vt NaN"] {
+            assert!(parser(line).is_err(), "{}", line);
+        }
+        let error = parse("v 0 0 0\nv 1 nan 0\n", Path::new("test.obj"), grey())
+            .err()
+            .unwrap();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn mtl_to_material() {
+        let source = "\
+newmtl lamp
+Ke 4 4 4
+newmtl glass
+d 0.5
+Ni 1.3
+newmtl plastic
+Pr 0.3
+newmtl chrome
+Kd 0.1 0.1 0.1
+Ks 0.9 0.9 0.9
+Ns 1000
+newmtl chalk
+Kd 0.9 0.9 0.9
+";
+        let materials = parse_mtl(source, Path::new("test.mtl")).unwrap();
+        assert!(matches!(materials["lamp"], Material::DiffuseLight { .. }));
+        assert!(matches!(
+            materials["glass"],
+            Material::Dielectric { ref_idx, .. } if ref_idx == 1.3
+        ));
+        assert!(matches!(
+            &materials["plastic"],
+            Material::Principled(p) if p.roughness == 0.3
+        ));
+        assert!(matches!(
+            materials["chrome"],
+            Material::Metal { fuzz, .. } if fuzz < 0.1
+        ));
+        assert!(matches!(materials["chalk"], Material::Lambertian { .. }));
+    }
+
+    #[test]
+    fn rejects_bad_mtl_values() {
+        let error = parse_mtl("newmtl glass\nd 0.5\nNi 0\n", Path::new("test.mtl"))
+            .err()
+            .unwrap();
+        assert_eq!(error.line, 3);
+        assert!(parse_mtl("newmtl glass\nKd 1 nan 1\n", Path::new("test.mtl")).is_err());
+        assert!(parse_mtl("Kd 1 1 1\n", Path::new("test.mtl")).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use std::f64::consts::PI;
+
+use crate::frame::Frame;
+use crate::ggx;
+use crate::hittable::Hittable;
+use crate::point::Point;
+use rand::Rng;
+
+/// A distribution of directions to sample, with its density per unit solid
+/// angle.
+pub enum Pdf<'a> {
+    /// Every direction equally likely.
+    Uniform,
+    /// Directions about the frame's y axis, weighted by their cosine to it.
+    Cosine(Frame),
+    /// Mirror images of `view`, a direction in the frame's coordinates,
+    /// about GGX microfacet normals that can be seen from it.
+    Ggx {
+        frame: Frame,
+        view: Point,
+        alpha: f64,
+    },
+    /// Directions from `origin` towards `objects`, as their `random` picks.
+    Hittable {
+        objects: &'a dyn Hittable,
+        origin: Point,
+    },
+    /// `first` with probability `weight`, otherwise `second`.
+    Mixture {
+        weight: f64,
+        first: Box<Pdf<'a>>,
+        second: Box<Pdf<'a>>,
+    },
+}
+
+impl<'a> Pdf<'a> {
+    /// The cosine weighted hemisphere about `normal`.
+    pub fn cosine(normal: Point) -> Self {
+        Pdf::Cosine(Frame::new(Point::default(), normal))
+    }
+
+    /// Reflections of the direction `view` off a rough surface facing
+    /// `normal`.
+    pub fn ggx(normal: Point, view: Point, alpha: f64) -> Self {
+        let frame = Frame::new(Point::default(), normal);
+        Pdf::Ggx {
+            frame,
+            view: frame.vector_to_local(view.unit_vector()),
+            alpha: alpha.max(ggx::MIN_ALPHA),
+        }
+    }
+
+    pub fn value(&self, direction: &Point) -> f64 {
+        match self {
+            Pdf::Uniform => 1.0 / (4.0 * PI),
+            Pdf::Cosine(frame) => {
+                let cosine = direction.unit_vector().dot(&frame.y);
+                cosine.max(0.0) / PI
+            }
+            Pdf::Ggx { frame, view, alpha } => {
+                let direction = frame.vector_to_local(direction.unit_vector());
+                ggx::reflection_pdf(view, &direction, *alpha)
+            }
+            Pdf::Hittable { objects, origin } => objects.pdf_value(origin, direction),
+            Pdf::Mixture {
+                weight,
+                first,
+                second,
+            } => weight * first.value(direction) + (1.0 - weight) * second.value(direction),
+        }
+    }
+
+    pub fn generate(&self) -> Point {
+        let mut rng = rand::thread_rng();
+        match self {
+            Pdf::Uniform => Point::random_unit_vector(),
+            Pdf::Cosine(frame) => {
+                let (r1, r2): (f64, f64) = (rng.gen_range(0.0, 1.0), rng.gen_range(0.0, 1.0));
+                let phi = 2.0 * PI * r1;
+                let radius = r2.sqrt();
+                frame.vector_to_world(Point::new(
+                    phi.cos() * radius,
+                    (1.0 - r2).sqrt(),
+                    phi.sin() * radius,
+                ))
+            }
+            Pdf::Ggx { frame, view, alpha } => {
+                let (u1, u2) = (rng.gen_range(0.0, 1.0), rng.gen_range(0.0, 1.0));
+                let h = ggx::sample_visible_normal(view, *alpha, u1, u2);
+                frame.vector_to_world((-*view).reflect(&h))
+            }
+            Pdf::Hittable { objects, origin } => objects.random(origin),
+            Pdf::Mixture {
+                weight,
+                first,
+                second,
+            } => {
+                if rng.gen_range(0.0, 1.0) < *weight {
+                    first.generate()
+                } else {
+                    second.generate()
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::material::Material;
+    use crate::pixel::Pixel;
+    use crate::sphere::Sphere;
+
+    /// Integrate `f` times the pdf's density over the sphere of directions,
+    /// with the midpoint rule on a grid of equal solid angle cells.
+    fn integrate(pdf: &Pdf, f: impl Fn(&Point) -> f64) -> f64 {
+        let n = 300;
+        let cell = 4.0 * PI / (2 * n * n) as f64;
+        let mut sum = 0.0;
+        for i in 0..n {
+            let y = -1.0 + 2.0 * (i as f64 + 0.5) / n as f64;
+            let r = (1.0 - y * y).sqrt();
+            for j in 0..2 * n {
+                let phi = PI * (j as f64 + 0.5) / n as f64;
+                let d = Point::new(r * phi.cos(), y, r * phi.sin());
+                sum += f(&d) * pdf.value(&d);
+            }
+        }
+        sum * cell
+    }
+
+    /// The mean of `f` over directions the pdf generates.
+    fn sample_mean(pdf: &Pdf, f: impl Fn(&Point) -> f64) -> f64 {
+        let n = 20000;
+        (0..n)
+            .map(|_| f(&pdf.generate().unit_vector()))
+            .sum::<f64>()
+            / n as f64
+    }
+
+    fn light() -> Sphere {
+        let material = Material::DiffuseLight {
+            emit: Pixel::new(1.0, 1.0, 1.0).into(),
+        };
+        Sphere::new(Point::new(3.0, 0.0, 4.0), 2.0, Box::new(material))
+    }
+
+    /// Check the density integrates to one and that generated directions
+    /// follow it, comparing the mean height of samples with its expectation.
+    fn assert_normalised(pdf: &Pdf) {
+        let total = integrate(pdf, |_| 1.0);
+        assert!((total - 1.0).abs() < 0.01, "integrates to {}", total);
+        let expected = integrate(pdf, |d| d.y);
+        let found = sample_mean(pdf, |d| d.y);
+        assert!((expected - found).abs() < 0.02, "{} != {}", expected, found);
+    }
+
+    #[test]
+    fn uniform_and_cosine() {
+        assert_normalised(&Pdf::Uniform);
+        assert_normalised(&Pdf::cosine(Point::new(0.0, 1.0, 0.0)));
+        assert_normalised(&Pdf::cosine(Point::new(1.0, 1.0, -0.5)));
+        // The mean cosine of a cosine weighted hemisphere is 2/3.
+        let mean = sample_mean(&Pdf::cosine(Point::new(0.0, 1.0, 0.0)), |d| d.y);
+        assert!((mean - 2.0 / 3.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn cosine_is_zero_below_the_surface() {
+        let pdf = Pdf::cosine(Point::new(0.0, 1.0, 0.0));
+        assert_eq!(pdf.value(&Point::new(0.3, -0.1, 0.0)), 0.0);
+        assert_eq!(pdf.value(&Point::new(0.0, 1.0, 0.0)), 1.0 / PI);
+    }
+
+    #[test]
+    fn ggx_loses_only_what_reflects_below_the_surface() {
+        let normal = Point::new(0.0, 1.0, 0.0);
+        for &alpha in &[0.2, 0.5] {
+            let head_on = Pdf::ggx(normal, normal, alpha);
+            // Seen head on, a reflection goes under the surface when its
+            // normal tilts past 45 degrees, which GGX gives a probability of
+            // alpha^2 / (1 + alpha^2).
+            let total = integrate(&head_on, |_| 1.0);
+            let expected = 1.0 / (1.0 + alpha * alpha);
+            assert!(
+                (total - expected).abs() < 0.005,
+                "{} != {}",
+                total,
+                expected
+            );
+
+            // Samples that stay above the surface follow the density.
+            let expected = integrate(&head_on, |d| d.y) / total;
+            let found = sample_mean(&head_on, |d| d.y.max(0.0))
+                / sample_mean(&head_on, |d| (d.y > 0.0) as u8 as f64);
+            assert!((expected - found).abs() < 0.02, "{} != {}", expected, found);
+        }
+    }
+
+    #[test]
+    fn hittable_and_mixture() {
+        let light = light();
+        let towards_light = || Pdf::Hittable {
+            objects: &light,
+            origin: Point::new(0.0, 0.0, 0.0),
+        };
+        assert_normalised(&towards_light());
+        assert_normalised(&Pdf::Mixture {
+            weight: 0.5,
+            first: Box::new(towards_light()),
+            second: Box::new(Pdf::cosine(Point::new(0.0, 0.0, 1.0))),
+        });
+    }
+}
@@ -0,0 +1,86 @@
+use rand::seq::SliceRandom;
+
+use crate::point::Point;
+
+const POINT_COUNT: usize = 256;
+
+/// Ken Perlin's gradient noise, from random unit vectors at the corners of
+/// the integer lattice.
+pub struct Perlin {
+    gradients: Vec<Point>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Perlin {
+    pub fn new() -> Self {
+        let gradients = (0..POINT_COUNT)
+            .map(|_| Point::random(-1.0, 1.0).unit_vector())
+            .collect();
+        let mut rng = rand::thread_rng();
+        let mut permutation = || {
+            let mut p = (0..POINT_COUNT).collect::<Vec<usize>>();
+            p.shuffle(&mut rng);
+            p
+        };
+        Perlin {
+            gradients,
+            perm_x: permutation(),
+            perm_y: permutation(),
+            perm_z: permutation(),
+        }
+    }
+
+    /// Smooth noise in about [-1, 1].
+    pub fn noise(&self, p: &Point) -> f64 {
+        let (fx, fy, fz) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (u, v, w) = (p.x - fx, p.y - fy, p.z - fz);
+        let (i, j, k) = (fx as i64, fy as i64, fz as i64);
+
+        // Hermite smoothing hides the lattice.
+        let (uu, vv, ww) = (
+            u * u * (3.0 - 2.0 * u),
+            v * v * (3.0 - 2.0 * v),
+            w * w * (3.0 - 2.0 * w),
+        );
+        let wrap = |n: i64| (n & (POINT_COUNT as i64 - 1)) as usize;
+        let mut sum = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let gradient = self.gradients[self.perm_x[wrap(i + di)]
+                        ^ self.perm_y[wrap(j + dj)]
+                        ^ self.perm_z[wrap(k + dk)]];
+                    let (di, dj, dk) = (di as f64, dj as f64, dk as f64);
+                    let weight = Point::new(u - di, v - dj, w - dk);
+                    sum += (di * uu + (1.0 - di) * (1.0 - uu))
+                        * (dj * vv + (1.0 - dj) * (1.0 - vv))
+                        * (dk * ww + (1.0 - dk) * (1.0 - ww))
+                        * gradient.dot(&weight);
+                }
+            }
+        }
+        sum
+    }
+
+    /// The sum of `depth` octaves of noise, each at twice the frequency and
+    /// half the weight of the last.
+    pub fn turbulence(&self, p: &Point, depth: usize) -> f64 {
+        let mut sum = 0.0;
+        let mut p = *p;
+        let mut weight = 1.0;
+        for _ in 0..depth {
+            sum += weight * self.noise(&p);
+            weight *= 0.5;
+            p = p * 2.0;
+        }
+        sum.abs()
+    }
+}
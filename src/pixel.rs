@@ -1,7 +1,10 @@
-use std::ops::{Add, AddAssign, Mul, MulAssign};
 use rand::Rng;
+use serde::Deserialize;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign};
 
-#[derive(Default, Clone, Debug, Copy)]
+#[derive(Default, Clone, Debug, Copy, PartialEq, Deserialize)]
+#[serde(from = "[f64; 3]")]
 pub struct Pixel {
     pub r: f64,
     pub g: f64,
@@ -31,6 +34,18 @@ impl Pixel {
     }
 }
 
+impl From<[f64; 3]> for Pixel {
+    fn from(p: [f64; 3]) -> Self {
+        Pixel::new(p[0], p[1], p[2])
+    }
+}
+
+impl fmt::Display for Pixel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.r, self.g, self.b)
+    }
+}
+
 impl Mul<f64> for Pixel {
     type Output = Self;
 
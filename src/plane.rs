@@ -0,0 +1,61 @@
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// An infinite plane through `point`, facing along `normal`.
+pub struct Plane {
+    pub point: Point,
+    pub normal: Point,
+    pub material: Box<Material>,
+}
+
+impl Plane {
+    pub fn new(point: Point, normal: Point, material: Box<Material>) -> Self {
+        Plane {
+            point,
+            normal: normal.unit_vector(),
+            material,
+        }
+    }
+
+    /// Two unit vectors spanning the plane, used for texture coordinates.
+    fn tangents(&self) -> (Point, Point) {
+        let other = if self.normal.x.abs() > 0.9 {
+            Point::new(0.0, 1.0, 0.0)
+        } else {
+            Point::new(1.0, 0.0, 0.0)
+        };
+        let tangent = self.normal.cross(&other).unit_vector();
+        (tangent, self.normal.cross(&tangent))
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        let denom = self.normal.dot(&ray.direction);
+        if denom.abs() < 1e-12 {
+            return false;
+        }
+        let t = (self.point - ray.origin).dot(&self.normal) / denom;
+        if t <= t_min || t >= t_max {
+            return false;
+        }
+
+        hit.t = t;
+        hit.point = ray.at(t);
+        let (tangent, bitangent) = self.tangents();
+        let planar = hit.point - self.point;
+        hit.u = planar.dot(&tangent);
+        hit.v = planar.dot(&bitangent);
+        hit.set_face_normal(ray, self.normal);
+        hit.material = self.material.clone();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+}
@@ -1,8 +1,10 @@
 use rand::Rng;
+use serde::Deserialize;
 use std::fmt;
 
-use std::ops::{Add, Div, Mul, Neg, Sub};
-#[derive(Default, Clone, Copy, Debug)]
+use std::ops::{Add, Div, Index, Mul, Neg, Sub};
+#[derive(Default, Clone, Copy, Debug, Deserialize)]
+#[serde(from = "[f64; 3]")]
 pub struct Point {
     pub x: f64,
     pub y: f64,
@@ -60,6 +62,10 @@ impl Point {
         self.z
     }
 
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
     pub fn length_squared(&self) -> f64 {
         self.x * self.x + self.y * self.y + self.z * self.z
     }
@@ -100,6 +106,24 @@ impl Point {
     }
 }
 
+impl From<[f64; 3]> for Point {
+    fn from(p: [f64; 3]) -> Self {
+        Point::new(p[0], p[1], p[2])
+    }
+}
+
+impl Index<usize> for Point {
+    type Output = f64;
+    fn index(&self, axis: usize) -> &f64 {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("axis {} out of range for Point", axis),
+        }
+    }
+}
+
 impl Div<f64> for Point {
     type Output = Self;
     fn div(self, t: f64) -> Self {
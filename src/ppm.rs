@@ -1,10 +1,21 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::hdr;
+use crate::tonemap::ToneMap;
+
+#[cfg(feature = "window")]
 use show_image::{ImageData, ImageInfo};
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Default, Clone, Debug)]
 pub struct PPM {
     pub width: usize,
     pub height: usize,
     pub pixels: Vec<f64>,
+    /// How `pixels` are converted for display and 8 bit formats.
+    pub tone_map: ToneMap,
 }
 
 impl PPM {
@@ -13,36 +24,284 @@ impl PPM {
             width,
             height,
             pixels: vec![0.0_f64; width * height * 3],
+            tone_map: ToneMap::default(),
         }
     }
+
+    /// Copy in a rendered row. Rows are rendered bottom up, so `row_index` 0
+    /// is the last row of the image.
+    pub fn set_row(&mut self, row_index: usize, row: &[f64]) {
+        let row_start = ((self.height * self.width) - (row_index + 1) * self.width) * 3;
+        let row_end = ((self.height * self.width) - row_index * self.width) * 3;
+        self.pixels[row_start..row_end].copy_from_slice(row);
+    }
+
+    /// Tone map and quantise the image to 8 bit RGB.
+    pub fn to_rgb8(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .map(|p| (255.999 * self.tone_map.apply(*p)) as u8)
+            .collect()
+    }
+
+    /// Save the image, picking the format from the extension of `path`.
+    /// `.ppm` files are written as binary P6 and `.hdr` files as Radiance
+    /// RGBE with the unclamped linear values, ignoring `tone_map`; anything
+    /// else goes through the `image` crate.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("ppm") => {
+                let file = File::create(path).map_err(|e| e.to_string())?;
+                self.write_p6(BufWriter::new(file))
+                    .map_err(|e| e.to_string())
+            }
+            Some("hdr") => {
+                let file = File::create(path).map_err(|e| e.to_string())?;
+                hdr::write(self, BufWriter::new(file)).map_err(|e| e.to_string())
+            }
+            _ => image::save_buffer(
+                path,
+                &self.to_rgb8(),
+                self.width as u32,
+                self.height as u32,
+                image::ColorType::Rgb8,
+            )
+            .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Write the image as a binary (P6) Netpbm file.
+    pub fn write_p6<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        w.write_all(&self.to_rgb8())?;
+        w.flush()
+    }
+
+    /// Write the image as an ASCII (P3) Netpbm file. Each row starts on a new
+    /// line, and lines wrap before the format's limit of 70 characters.
+    pub fn write_p3<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write!(w, "P3\n{} {}\n255\n", self.width, self.height)?;
+        for row in self.to_rgb8().chunks(self.width * 3) {
+            let mut line = String::new();
+            for v in row {
+                let v = v.to_string();
+                if !line.is_empty() && line.len() + 1 + v.len() > 70 {
+                    writeln!(w, "{}", line)?;
+                    line.clear();
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(&v);
+            }
+            writeln!(w, "{}", line)?;
+        }
+        w.flush()
+    }
+
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Read a P3 or P6 Netpbm file, undoing the default gamma 2.0 tone
+    /// mapping.
+    pub fn read<R: BufRead>(mut r: R) -> io::Result<Self> {
+        let magic = read_token(&mut r)?;
+        if magic != "P3" && magic != "P6" {
+            return Err(invalid_data(format!(
+                "unsupported magic number {:?}",
+                magic
+            )));
+        }
+        let width = read_number(&mut r, "width")?;
+        let height = read_number(&mut r, "height")?;
+        let maxval = read_number(&mut r, "maxval")?;
+        if maxval == 0 || maxval > 65535 {
+            return Err(invalid_data(format!("maxval {} out of range", maxval)));
+        }
+
+        // The header is untrusted, so buffers grow with the data actually
+        // read rather than being sized from it up front.
+        let len = width
+            .checked_mul(height)
+            .and_then(|n| n.checked_mul(3))
+            .ok_or_else(|| invalid_data(format!("{}x{} image is too large", width, height)))?;
+        let mut pixels = Vec::new();
+        if magic == "P3" {
+            for _ in 0..len {
+                pixels.push(decode(read_number(&mut r, "sample")?, maxval));
+            }
+        } else if maxval < 256 {
+            let data = read_raster(&mut r, len)?;
+            pixels.extend(data.iter().map(|v| decode(*v as usize, maxval)));
+        } else {
+            let bytes = len
+                .checked_mul(2)
+                .ok_or_else(|| invalid_data(format!("{}x{} image is too large", width, height)))?;
+            let data = read_raster(&mut r, bytes)?;
+            pixels.extend(
+                data.chunks(2)
+                    .map(|v| decode(u16::from_be_bytes([v[0], v[1]]) as usize, maxval)),
+            );
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+            tone_map: ToneMap::default(),
+        })
+    }
+}
+
+fn decode(v: usize, maxval: usize) -> f64 {
+    let p = v.min(maxval) as f64 / maxval as f64;
+    p * p
 }
 
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Read the next whitespace separated header token, skipping `#` comments.
+/// Consumes the single whitespace byte that ends the token, as required
+/// before P6 raster data.
+fn read_token<R: BufRead>(r: &mut R) -> io::Result<String> {
+    let mut token = String::new();
+    let mut byte = [0_u8; 1];
+    loop {
+        if r.read(&mut byte)? == 0 {
+            if token.is_empty() {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            return Ok(token);
+        }
+        match byte[0] {
+            b'#' if token.is_empty() => {
+                r.read_until(b'\n', &mut Vec::new())?;
+            }
+            b if b.is_ascii_whitespace() => {
+                if !token.is_empty() {
+                    return Ok(token);
+                }
+            }
+            b => token.push(b as char),
+        }
+    }
+}
+
+/// Read exactly `len` bytes of P6 raster data.
+fn read_raster<R: BufRead>(r: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    r.take(len as u64).read_to_end(&mut data)?;
+    if data.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(data)
+}
+
+fn read_number<R: BufRead>(r: &mut R, what: &str) -> io::Result<usize> {
+    let token = read_token(r)?;
+    token
+        .parse()
+        .map_err(|_| invalid_data(format!("invalid {} {:?}", what, token)))
+}
+
+#[cfg(feature = "window")]
 impl ImageData for &PPM {
     fn info(&self) -> Result<ImageInfo, String> {
         Ok(ImageInfo::rgb8(self.width, self.height))
     }
 
     fn data(self) -> Box<[u8]> {
-        const LESS_THAN_ONE: f64 = 1.0 - f64::MIN;
-        self.pixels
-            .iter()
-            .map(|p| (255.999 * p.sqrt().clamp(0.0, LESS_THAN_ONE)) as u8)
-            .collect::<Vec<u8>>()
-            .into_boxed_slice()
+        self.to_rgb8().into_boxed_slice()
     }
 }
 
+#[cfg(feature = "window")]
 impl ImageData for PPM {
     fn info(&self) -> Result<ImageInfo, String> {
         Ok(ImageInfo::rgb8(self.width, self.height))
     }
 
     fn data(self) -> Box<[u8]> {
-        const LESS_THAN_ONE: f64 = 1.0 - f64::MIN;
-        self.pixels
-            .iter()
-            .map(|p| (255.999 * p.sqrt().clamp(0.0, LESS_THAN_ONE)) as u8)
-            .collect::<Vec<u8>>()
-            .into_boxed_slice()
+        self.to_rgb8().into_boxed_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient() -> PPM {
+        let mut image = PPM::new(4, 3);
+        for (i, p) in image.pixels.iter_mut().enumerate() {
+            *p = i as f64 / 35.0;
+        }
+        image
+    }
+
+    fn assert_round_trip(written: &[u8], original: &PPM) {
+        let read = PPM::read(written).unwrap();
+        assert_eq!((read.width, read.height), (original.width, original.height));
+        assert_eq!(read.to_rgb8(), original.to_rgb8());
+    }
+
+    #[test]
+    fn p6_round_trip() {
+        let image = gradient();
+        let mut written = Vec::new();
+        image.write_p6(&mut written).unwrap();
+        assert_round_trip(&written, &image);
+    }
+
+    #[test]
+    fn p3_round_trip() {
+        let image = gradient();
+        let mut written = Vec::new();
+        image.write_p3(&mut written).unwrap();
+        assert_round_trip(&written, &image);
+    }
+
+    #[test]
+    fn p3_lines_fit_in_70_columns() {
+        let mut image = PPM::new(40, 2);
+        for p in image.pixels.iter_mut() {
+            *p = 1.0;
+        }
+        let mut written = Vec::new();
+        image.write_p3(&mut written).unwrap();
+        let text = String::from_utf8(written).unwrap();
+        assert!(text.lines().all(|line| line.len() <= 70));
+        // Three header lines, then each row spread over several lines.
+        assert!(text.lines().count() > 3 + image.height);
+        assert_round_trip(text.as_bytes(), &image);
+    }
+
+    #[test]
+    fn reads_comments_and_16_bit_samples() {
+        let mut data = b"P6\n# a comment\n1 1\n65535\n".to_vec();
+        data.extend_from_slice(&[0xff, 0xff, 0x00, 0x00, 0x80, 0x00]);
+        let image = PPM::read(&data[..]).unwrap();
+        assert_eq!(image.pixels[0], 1.0);
+        assert_eq!(image.pixels[1], 0.0);
+        assert!((image.pixels[2] - 0.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn rejects_oversized_header() {
+        let data = format!("P6\n{} 2\n255\n", usize::MAX);
+        let err = PPM::read(data.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_truncated_raster() {
+        let err = PPM::read(&b"P6\n100000 100000\n255\n\x01\x02"[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
     }
 }
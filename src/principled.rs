@@ -0,0 +1,101 @@
+use std::f64::consts::PI;
+
+use crate::frame::Frame;
+use crate::ggx::{self, schlick};
+use crate::pdf::Pdf;
+use crate::pixel::Pixel;
+use crate::point::Point;
+use crate::texture::Texture;
+
+/// A surface in the style of Disney's and Blender's principled shaders: a
+/// diffuse base under a GGX specular layer, turning into a metal tinted by
+/// `base_color` as `metallic` goes to 1, under an optional clear coat.
+#[derive(Clone)]
+pub struct Principled {
+    pub base_color: Texture,
+    /// Perceptual roughness from 0 to 1; the GGX width is its square.
+    pub roughness: f64,
+    pub metallic: f64,
+    /// The non-metal's reflectance at normal incidence, as a fraction of 8%,
+    /// so the default of 0.5 suits most dielectrics.
+    pub specular: f64,
+    /// The strength of a colourless varnish on top.
+    pub clearcoat: f64,
+    pub clearcoat_roughness: f64,
+}
+
+impl Default for Principled {
+    fn default() -> Self {
+        Principled {
+            base_color: Pixel::new(0.8, 0.8, 0.8).into(),
+            roughness: 0.5,
+            metallic: 0.0,
+            specular: 0.5,
+            clearcoat: 0.0,
+            clearcoat_roughness: 0.03,
+        }
+    }
+}
+
+/// The clear coat reflects as glass of index 1.5 does.
+const CLEARCOAT_F0: f64 = 0.04;
+
+impl Principled {
+    /// The BSDF times the cosine for light arriving from `wi` and leaving
+    /// towards `wo`, both unit vectors, at a surface facing `normal` whose
+    /// base colour there is `base`.
+    pub fn eval(&self, base: Pixel, normal: Point, wo: Point, wi: Point) -> Pixel {
+        let frame = Frame::new(Point::default(), normal);
+        let (wo, wi) = (frame.vector_to_local(wo), frame.vector_to_local(wi));
+        if wo.y <= 0.0 || wi.y <= 0.0 {
+            return Pixel::new(0.0, 0.0, 0.0);
+        }
+        let h = (wo + wi).unit_vector();
+        let cos_h = wi.dot(&h);
+
+        let alpha = ggx::alpha(self.roughness);
+        let microfacets = ggx::d(&h, alpha) * ggx::g2(&wo, &wi, alpha) / (4.0 * wo.y);
+        let f0_dielectric = 0.08 * self.specular;
+        // Metals reflect their base colour rather than white.
+        let fresnel = |c: f64| {
+            let f0 = f0_dielectric + (c - f0_dielectric) * self.metallic;
+            schlick(f0, cos_h) * microfacets
+        };
+        let specular = Pixel::new(fresnel(base.r), fresnel(base.g), fresnel(base.b));
+
+        // The diffuse base only gets light the specular layer lets through.
+        let transmitted = 1.0 - schlick(f0_dielectric, wo.y);
+        let diffuse = base * ((1.0 - self.metallic) * transmitted * wi.y / PI);
+
+        let value = diffuse + specular;
+        if self.clearcoat <= 0.0 {
+            return value;
+        }
+        let alpha = ggx::alpha(self.clearcoat_roughness);
+        let coat = self.clearcoat
+            * schlick(CLEARCOAT_F0, cos_h)
+            * ggx::d(&h, alpha)
+            * ggx::g2(&wo, &wi, alpha)
+            / (4.0 * wo.y);
+        let through_coat = 1.0 - self.clearcoat * schlick(CLEARCOAT_F0, wo.y);
+        value * through_coat + Pixel::new(coat, coat, coat)
+    }
+
+    /// Directions to sample for light leaving towards `wo`, picking each
+    /// layer roughly as often as it matters.
+    pub fn sampling_pdf(&self, normal: Point, wo: Point) -> Pdf<'static> {
+        let base = Pdf::Mixture {
+            weight: 0.5 + 0.5 * self.metallic,
+            first: Box::new(Pdf::ggx(normal, wo, ggx::alpha(self.roughness))),
+            second: Box::new(Pdf::cosine(normal)),
+        };
+        if self.clearcoat <= 0.0 {
+            return base;
+        }
+        Pdf::Mixture {
+            weight: 0.25 * self.clearcoat,
+            first: Box::new(Pdf::ggx(normal, wo, ggx::alpha(self.clearcoat_roughness))),
+            second: Box::new(base),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::hittable_list::HittableList;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+use rand::Rng;
+
+/// A parallelogram with one corner at `corner` and edges `u` and `v`. The
+/// front face is the side `u × v` points to.
+pub struct Quad {
+    pub corner: Point,
+    pub u: Point,
+    pub v: Point,
+    pub material: Box<Material>,
+    normal: Point,
+    /// `n / n·n` for the unnormalised normal `n`, which turns a point in the
+    /// plane into its coordinates along `u` and `v`.
+    w: Point,
+}
+
+impl Quad {
+    pub fn new(corner: Point, u: Point, v: Point, material: Box<Material>) -> Self {
+        let n = u.cross(&v);
+        Quad {
+            corner,
+            u,
+            v,
+            material,
+            normal: n.unit_vector(),
+            w: n / n.dot(&n),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        let denom = self.normal.dot(&ray.direction);
+        if denom.abs() < 1e-12 {
+            return false;
+        }
+        let t = (self.corner - ray.origin).dot(&self.normal) / denom;
+        if t <= t_min || t >= t_max {
+            return false;
+        }
+
+        let point = ray.at(t);
+        let planar = point - self.corner;
+        let alpha = self.w.dot(&planar.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return false;
+        }
+
+        hit.t = t;
+        hit.point = point;
+        hit.u = alpha;
+        hit.v = beta;
+        hit.set_face_normal(ray, self.normal);
+        hit.material = self.material.clone();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let diagonal = Aabb::from_points(self.corner, self.corner + self.u + self.v);
+        let other = Aabb::from_points(self.corner + self.u, self.corner + self.v);
+        Some(diagonal.union(&other).padded(1e-4))
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Point) -> f64 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        let mut hit = HitRecord::default();
+        if !self.hit(&ray, 0.001, f64::INFINITY, &mut hit) {
+            return 0.0;
+        }
+        // Convert the uniform density over the area to one over solid angle.
+        let distance_squared = hit.t * hit.t * direction.length_squared();
+        let cosine = (direction.dot(&self.normal) / direction.length()).abs();
+        let area = self.u.cross(&self.v).length();
+        distance_squared / (cosine * area)
+    }
+
+    fn random(&self, origin: &Point) -> Point {
+        let mut rng = rand::thread_rng();
+        let p = self.corner + self.u * rng.gen_range(0.0, 1.0) + self.v * rng.gen_range(0.0, 1.0);
+        p - *origin
+    }
+}
+
+/// An axis-aligned box made of six outward facing quads.
+pub struct Cuboid {
+    pub min: Point,
+    pub max: Point,
+    sides: HittableList,
+}
+
+impl Cuboid {
+    /// The box with `a` and `b` as opposite corners, in any order.
+    pub fn new(a: Point, b: Point, material: Box<Material>) -> Self {
+        let bbox = Aabb::from_points(a, b);
+        let (min, max) = (bbox.min, bbox.max);
+        let dx = Point::new(max.x - min.x, 0.0, 0.0);
+        let dy = Point::new(0.0, max.y - min.y, 0.0);
+        let dz = Point::new(0.0, 0.0, max.z - min.z);
+
+        let mut sides = HittableList::default();
+        let mut add = |corner, u, v| sides.add(Box::new(Quad::new(corner, u, v, material.clone())));
+        add(Point::new(min.x, min.y, max.z), dx, dy); // front
+        add(Point::new(max.x, min.y, max.z), -dz, dy); // right
+        add(Point::new(max.x, min.y, min.z), -dx, dy); // back
+        add(Point::new(min.x, min.y, min.z), dz, dy); // left
+        add(Point::new(min.x, max.y, max.z), dx, -dz); // top
+        add(Point::new(min.x, min.y, min.z), dx, dz); // bottom
+
+        Cuboid { min, max, sides }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        self.sides.hit(ray, t_min, t_max, hit)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::new(self.min, self.max).padded(1e-4))
+    }
+}
@@ -0,0 +1,159 @@
+use std::ops::Mul;
+
+use crate::point::Point;
+
+/// A quaternion `w + xi + yj + zk`. Unit quaternions represent rotations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quat {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Quat {
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self { w, x, y, z }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    /// A right-handed rotation of `angle` radians about `axis`.
+    pub fn from_axis_angle(axis: Point, angle: f64) -> Self {
+        let axis = axis.unit_vector();
+        let (s, c) = (angle / 2.0).sin_cos();
+        Self::new(c, axis.x * s, axis.y * s, axis.z * s)
+    }
+
+    pub fn dot(&self, other: &Quat) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn length(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(self) -> Quat {
+        let l = self.length();
+        Self::new(self.w / l, self.x / l, self.y / l, self.z / l)
+    }
+
+    pub fn conjugate(&self) -> Quat {
+        Self::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    /// The inverse rotation. For unit quaternions this is the conjugate.
+    pub fn inverse(&self) -> Quat {
+        let c = self.conjugate();
+        let l2 = self.dot(self);
+        Self::new(c.w / l2, c.x / l2, c.y / l2, c.z / l2)
+    }
+
+    /// Rotate `v` by this unit quaternion.
+    pub fn rotate(&self, v: Point) -> Point {
+        let q = Point::new(self.x, self.y, self.z);
+        let t = q.cross(&v) * 2.0;
+        v + t * self.w + q.cross(&t)
+    }
+
+    /// Spherical linear interpolation from `self` at `t` = 0 to `other` at
+    /// `t` = 1, along the shorter arc.
+    pub fn slerp(&self, other: &Quat, t: f64) -> Quat {
+        let mut other = *other;
+        let mut cos = self.dot(&other);
+        if cos < 0.0 {
+            other = Self::new(-other.w, -other.x, -other.y, -other.z);
+            cos = -cos;
+        }
+        let (a, b) = if cos > 0.9995 {
+            // Nearly parallel: lerp to avoid dividing by a tiny sine.
+            (1.0 - t, t)
+        } else {
+            let theta = cos.acos();
+            let sin = theta.sin();
+            (((1.0 - t) * theta).sin() / sin, (t * theta).sin() / sin)
+        };
+        Self::new(
+            a * self.w + b * other.w,
+            a * self.x + b * other.x,
+            a * self.y + b * other.y,
+            a * self.z + b * other.z,
+        )
+        .normalize()
+    }
+}
+
+/// The Hamilton product: `a * b` rotates by `b` then by `a`.
+impl Mul for Quat {
+    type Output = Quat;
+
+    fn mul(self, rhs: Quat) -> Quat {
+        Quat::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    fn assert_quat_close(a: Quat, b: Quat) {
+        let d = Quat::new(a.w - b.w, a.x - b.x, a.y - b.y, a.z - b.z);
+        assert!(d.length() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn times_inverse_is_identity() {
+        let quats = [
+            Quat::from_axis_angle(Point::new(1.0, 2.0, 3.0), 0.8),
+            Quat::new(1.0, 2.0, 3.0, 4.0),
+            Quat::new(0.5, -0.5, 0.5, -0.5),
+        ];
+        for q in &quats {
+            assert_quat_close(*q * q.inverse(), Quat::identity());
+            assert_quat_close(q.inverse() * *q, Quat::identity());
+        }
+    }
+
+    #[test]
+    fn slerp_endpoints_and_midpoint() {
+        let axis = Point::new(0.0, 1.0, 0.0);
+        let a = Quat::from_axis_angle(axis, 0.0);
+        let b = Quat::from_axis_angle(axis, FRAC_PI_2);
+        assert_quat_close(a.slerp(&b, 0.0), a);
+        assert_quat_close(a.slerp(&b, 1.0), b);
+        assert_quat_close(a.slerp(&b, 0.5), Quat::from_axis_angle(axis, FRAC_PI_4));
+    }
+
+    #[test]
+    fn slerp_takes_the_shorter_arc() {
+        let axis = Point::new(0.0, 0.0, 1.0);
+        let a = Quat::identity();
+        let b = Quat::from_axis_angle(axis, FRAC_PI_2);
+        let negated = Quat::new(-b.w, -b.x, -b.y, -b.z);
+        assert_quat_close(
+            a.slerp(&negated, 0.5),
+            Quat::from_axis_angle(axis, FRAC_PI_4),
+        );
+    }
+
+    #[test]
+    fn rotates_like_the_axis_angle() {
+        let q = Quat::from_axis_angle(Point::new(0.0, 0.0, 1.0), FRAC_PI_2);
+        let v = q.rotate(Point::new(1.0, 0.0, 0.0));
+        assert!((v - Point::new(0.0, 1.0, 0.0)).length() < 1e-9);
+    }
+}
@@ -4,11 +4,31 @@ use crate::point::Point;
 pub struct Ray {
     pub origin: Point,
     pub direction: Point,
+    /// When the ray was cast, for motion blur.
+    pub time: f64,
+    /// The wavelength in nanometres the ray carries in a spectral render.
+    pub wavelength: Option<f64>,
 }
 
 impl Ray {
-    pub fn new(origin: Point, direction: Point) -> Self {
-        Self { origin, direction }
+    pub fn new(origin: Point, direction: Point, time: f64) -> Self {
+        Self {
+            origin,
+            direction,
+            time,
+            wavelength: None,
+        }
+    }
+
+    /// A ray from `origin` along `direction` at this ray's time and
+    /// wavelength, for light scattering on from a hit.
+    pub fn scattered(&self, origin: Point, direction: Point) -> Self {
+        Self {
+            origin,
+            direction,
+            time: self.time,
+            wavelength: self.wavelength,
+        }
     }
     // pub fn origin(&self) -> Point {
     //     self.origin
@@ -0,0 +1,292 @@
+use rand::Rng;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// A rectangle in the plane z = k, facing +z.
+pub struct XyRect {
+    pub x0: f64,
+    pub x1: f64,
+    pub y0: f64,
+    pub y1: f64,
+    pub k: f64,
+    pub material: Box<Material>,
+}
+
+/// A rectangle in the plane y = k, facing +y.
+pub struct XzRect {
+    pub x0: f64,
+    pub x1: f64,
+    pub z0: f64,
+    pub z1: f64,
+    pub k: f64,
+    pub material: Box<Material>,
+}
+
+/// A rectangle in the plane x = k, facing +x.
+pub struct YzRect {
+    pub y0: f64,
+    pub y1: f64,
+    pub z0: f64,
+    pub z1: f64,
+    pub k: f64,
+    pub material: Box<Material>,
+}
+
+impl XyRect {
+    /// The rectangle between `x0` and `x1` and between `y0` and `y1`, each
+    /// pair in any order.
+    pub fn new(x0: f64, x1: f64, y0: f64, y1: f64, k: f64, material: Box<Material>) -> Self {
+        XyRect {
+            x0: x0.min(x1),
+            x1: x0.max(x1),
+            y0: y0.min(y1),
+            y1: y0.max(y1),
+            k,
+            material,
+        }
+    }
+}
+
+impl XzRect {
+    /// The rectangle between `x0` and `x1` and between `z0` and `z1`, each
+    /// pair in any order.
+    pub fn new(x0: f64, x1: f64, z0: f64, z1: f64, k: f64, material: Box<Material>) -> Self {
+        XzRect {
+            x0: x0.min(x1),
+            x1: x0.max(x1),
+            z0: z0.min(z1),
+            z1: z0.max(z1),
+            k,
+            material,
+        }
+    }
+}
+
+impl YzRect {
+    /// The rectangle between `y0` and `y1` and between `z0` and `z1`, each
+    /// pair in any order.
+    pub fn new(y0: f64, y1: f64, z0: f64, z1: f64, k: f64, material: Box<Material>) -> Self {
+        YzRect {
+            y0: y0.min(y1),
+            y1: y0.max(y1),
+            z0: z0.min(z1),
+            z1: z0.max(z1),
+            k,
+            material,
+        }
+    }
+}
+
+/// The extent of a rectangle along its two in-plane axes `a` and `b` and its
+/// offset `k` along the third axis `c`, given as indices into a `Point`.
+struct Extent {
+    axes: (usize, usize, usize),
+    a: (f64, f64),
+    b: (f64, f64),
+    k: f64,
+}
+
+impl Extent {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        let (a, b, c) = self.axes;
+        let t = (self.k - ray.origin[c]) / ray.direction[c];
+        // A ray parallel to the rectangle gives an infinite or NaN `t`.
+        if !(t > t_min && t < t_max) {
+            return false;
+        }
+        let p = ray.at(t);
+        if p[a] < self.a.0 || p[a] > self.a.1 || p[b] < self.b.0 || p[b] > self.b.1 {
+            return false;
+        }
+
+        hit.t = t;
+        hit.point = p;
+        hit.u = (p[a] - self.a.0) / (self.a.1 - self.a.0);
+        hit.v = (p[b] - self.b.0) / (self.b.1 - self.b.0);
+        let mut normal = [0.0; 3];
+        normal[c] = 1.0;
+        hit.set_face_normal(ray, Point::from(normal));
+        true
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Point) -> f64 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        let mut hit = HitRecord::default();
+        if !self.hit(&ray, 0.001, f64::INFINITY, &mut hit) {
+            return 0.0;
+        }
+        // Convert the uniform density over the area to one over solid angle.
+        let distance_squared = hit.t * hit.t * direction.length_squared();
+        let cosine = (direction[self.axes.2] / direction.length()).abs();
+        let area = (self.a.1 - self.a.0) * (self.b.1 - self.b.0);
+        distance_squared / (cosine * area)
+    }
+
+    fn random(&self, origin: &Point) -> Point {
+        let (a, b, c) = self.axes;
+        let mut rng = rand::thread_rng();
+        let mut p = [0.0; 3];
+        p[a] = self.a.0 + (self.a.1 - self.a.0) * rng.gen::<f64>();
+        p[b] = self.b.0 + (self.b.1 - self.b.0) * rng.gen::<f64>();
+        p[c] = self.k;
+        Point::from(p) - *origin
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let (a, b, c) = self.axes;
+        let mut min = [0.0; 3];
+        let mut max = [0.0; 3];
+        min[a] = self.a.0;
+        max[a] = self.a.1;
+        min[b] = self.b.0;
+        max[b] = self.b.1;
+        min[c] = self.k;
+        max[c] = self.k;
+        Aabb::new(Point::from(min), Point::from(max)).padded(1e-4)
+    }
+}
+
+impl XyRect {
+    fn extent(&self) -> Extent {
+        Extent {
+            axes: (0, 1, 2),
+            a: (self.x0, self.x1),
+            b: (self.y0, self.y1),
+            k: self.k,
+        }
+    }
+}
+
+impl XzRect {
+    fn extent(&self) -> Extent {
+        Extent {
+            axes: (0, 2, 1),
+            a: (self.x0, self.x1),
+            b: (self.z0, self.z1),
+            k: self.k,
+        }
+    }
+}
+
+impl YzRect {
+    fn extent(&self) -> Extent {
+        Extent {
+            axes: (1, 2, 0),
+            a: (self.y0, self.y1),
+            b: (self.z0, self.z1),
+            k: self.k,
+        }
+    }
+}
+
+impl Hittable for XyRect {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        if !self.extent().hit(ray, t_min, t_max, hit) {
+            return false;
+        }
+        hit.material = self.material.clone();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.extent().bounding_box())
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Point) -> f64 {
+        self.extent().pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point) -> Point {
+        self.extent().random(origin)
+    }
+}
+
+impl Hittable for XzRect {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        if !self.extent().hit(ray, t_min, t_max, hit) {
+            return false;
+        }
+        hit.material = self.material.clone();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.extent().bounding_box())
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Point) -> f64 {
+        self.extent().pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point) -> Point {
+        self.extent().random(origin)
+    }
+}
+
+impl Hittable for YzRect {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        if !self.extent().hit(ray, t_min, t_max, hit) {
+            return false;
+        }
+        hit.material = self.material.clone();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.extent().bounding_box())
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Point) -> f64 {
+        self.extent().pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point) -> Point {
+        self.extent().random(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::Pixel;
+
+    fn material() -> Box<Material> {
+        Box::new(Material::Lambertian {
+            albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+        })
+    }
+
+    #[test]
+    fn reversed_bounds_are_normalised() {
+        let rect = XzRect::new(1.0, -1.0, 2.0, -2.0, 3.0, material());
+        assert_eq!((rect.x0, rect.x1, rect.z0, rect.z1), (-1.0, 1.0, -2.0, 2.0));
+        let bbox = rect.bounding_box().unwrap();
+        assert!(bbox.min.x < bbox.max.x && bbox.min.z < bbox.max.z);
+
+        let ray = Ray::new(Point::new(0.5, 0.0, 0.5), Point::new(0.0, 1.0, 0.0), 0.0);
+        let mut hit = HitRecord::default();
+        assert!(rect.hit(&ray, 0.001, f64::INFINITY, &mut hit));
+        assert!((hit.t - 3.0).abs() < 1e-9);
+        assert!((hit.u - 0.75).abs() < 1e-9 && (hit.v - 0.625).abs() < 1e-9);
+    }
+
+    #[test]
+    fn samples_points_on_the_rectangle() {
+        let rect = XyRect::new(-1.0, 1.0, -1.0, 1.0, 2.0, material());
+        let origin = Point::new(0.0, 0.0, 0.0);
+        // Straight on, the density is the squared distance over the area.
+        let pdf = rect.pdf_value(&origin, &Point::new(0.0, 0.0, 1.0));
+        assert!((pdf - 1.0).abs() < 1e-9);
+        assert_eq!(rect.pdf_value(&origin, &Point::new(0.0, 0.0, -1.0)), 0.0);
+        for _ in 0..100 {
+            let p = rect.random(&origin);
+            assert!(p.x.abs() <= 1.0 && p.y.abs() <= 1.0 && p.z == 2.0);
+            assert!(rect.pdf_value(&origin, &p) > 0.0);
+        }
+    }
+}
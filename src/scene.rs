@@ -0,0 +1,1193 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::background::Background;
+use crate::camera::Camera;
+use crate::cone::Cone;
+use crate::constant_medium::ConstantMedium;
+use crate::csg::{Csg, CsgOp};
+use crate::cylinder::Cylinder;
+use crate::disk::Disk;
+use crate::hittable::Hittable;
+use crate::hittable_list::HittableList;
+use crate::mat4::Mat4;
+use crate::material::Material;
+use crate::moving_sphere::MovingSphere;
+use crate::obj;
+use crate::pixel::Pixel;
+use crate::plane::Plane;
+use crate::point::Point;
+use crate::principled::Principled;
+use crate::quad::{Cuboid, Quad};
+use crate::rect::{XyRect, XzRect, YzRect};
+use crate::spectrum::{Dispersion, D_LINE};
+use crate::sphere::Sphere;
+use crate::texture::{ImageTexture, Texture, Wrap};
+use crate::tonemap::{ToneMap, ToneMapper};
+use crate::torus::Torus;
+use crate::transform::Transform;
+use crate::triangle::Triangle;
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        key: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            SceneError::Invalid {
+                path,
+                line,
+                key,
+                message,
+            } => write!(f, "{}:{}: `{}`: {}", path.display(), line, key, message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneError::Io { source, .. } => Some(source),
+            SceneError::Invalid { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImageSettings {
+    pub width: usize,
+    pub aspect_ratio: f64,
+    pub samples_per_pixel: usize,
+    pub max_depth: usize,
+    pub tone_map: ToneMapper,
+    /// Exposure adjustment in stops, applied before tone mapping.
+    pub exposure: f64,
+    /// Trace a single wavelength per path rather than RGB, so dispersive
+    /// glass splits light into colours.
+    pub spectral: bool,
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            width: 3840,
+            aspect_ratio: 16.0 / 9.0,
+            samples_per_pixel: 500,
+            max_depth: 200,
+            tone_map: ToneMapper::default(),
+            exposure: 0.0,
+            spectral: false,
+        }
+    }
+}
+
+impl ImageSettings {
+    pub fn height(&self) -> usize {
+        (self.width as f64 / self.aspect_ratio) as usize
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        // Pixels are spread from one edge of the view to the other, which
+        // takes at least two in each direction.
+        if self.width < 2 {
+            return Err("width must be at least 2".to_string());
+        }
+        if !(self.aspect_ratio.is_finite() && self.aspect_ratio > 0.0) {
+            return Err("aspect_ratio must be greater than 0".to_string());
+        }
+        if self.height() < 2 {
+            return Err(format!(
+                "width {} at aspect_ratio {} gives an image with fewer than 2 rows",
+                self.width, self.aspect_ratio
+            ));
+        }
+        if self.samples_per_pixel == 0 {
+            return Err("samples_per_pixel must be at least 1".to_string());
+        }
+        if self.max_depth == 0 {
+            return Err("max_depth must be at least 1".to_string());
+        }
+        if !self.exposure.is_finite() {
+            return Err("exposure must be a finite number".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn tone_map(&self) -> ToneMap {
+        ToneMap::new(self.tone_map, self.exposure)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraSettings {
+    pub lookfrom: Point,
+    pub lookat: Point,
+    pub vup: Point,
+    pub vfov: f64,
+    pub aperture: f64,
+    pub focus_dist: f64,
+    /// When the shutter opens and closes, for motion blur.
+    pub shutter_open: f64,
+    pub shutter_close: f64,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            lookfrom: Point::new(13.0, 2.0, 3.0),
+            lookat: Point::new(0.0, 0.0, 0.0),
+            vup: Point::new(0.0, 1.0, 0.0),
+            vfov: 20.0,
+            aperture: 0.1,
+            focus_dist: 10.0,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+        }
+    }
+}
+
+impl CameraSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.vfov > 0.0 && self.vfov < 180.0) {
+            return Err("vfov must be between 0 and 180 degrees".to_string());
+        }
+        if !(self.aperture.is_finite() && self.aperture >= 0.0) {
+            return Err("aperture must not be negative".to_string());
+        }
+        if !(self.focus_dist.is_finite() && self.focus_dist > 0.0) {
+            return Err("focus_dist must be greater than 0".to_string());
+        }
+        if !(self.shutter_open.is_finite() && self.shutter_close.is_finite()) {
+            return Err("shutter times must be finite".to_string());
+        }
+        if !(self.lookfrom.is_finite() && self.lookat.is_finite() && self.vup.is_finite()) {
+            return Err("lookfrom, lookat and vup must be finite".to_string());
+        }
+        if self.shutter_close < self.shutter_open {
+            return Err("shutter_close must not be before shutter_open".to_string());
+        }
+        let view = self.lookat - self.lookfrom;
+        if view.length_squared() == 0.0 {
+            return Err("lookfrom and lookat must be different points".to_string());
+        }
+        if view.cross(&self.vup).length_squared() == 0.0 {
+            return Err("vup must not be parallel to the view direction".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub struct Scene {
+    pub image: ImageSettings,
+    pub camera: CameraSettings,
+    pub background: Background,
+    pub world: HittableList,
+    /// Emitters in `world` that can be sampled directly: spheres, quads and
+    /// rects with a `DiffuseLight` material and no transform.
+    pub lights: HittableList,
+}
+
+impl Scene {
+    pub fn new(world: HittableList) -> Self {
+        Self {
+            image: ImageSettings::default(),
+            camera: CameraSettings::default(),
+            background: Background::default(),
+            world,
+            lights: HittableList::default(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SceneError> {
+        let source = fs::read_to_string(path).map_err(|source| SceneError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&source, path)
+    }
+
+    /// Parse the TOML scene description in `source`. `path` is used when
+    /// reporting errors and to resolve files the scene refers to.
+    pub fn parse(source: &str, path: &Path) -> Result<Self, SceneError> {
+        let invalid = |span: Option<Range<usize>>, message: String| {
+            let (line, key) = locate(source, span.map_or(0, |s| s.start));
+            SceneError::Invalid {
+                path: path.to_path_buf(),
+                line,
+                key,
+                message,
+            }
+        };
+
+        let file: SceneFile =
+            toml::from_str(source).map_err(|e| invalid(e.span(), e.message().to_string()))?;
+
+        let background = match file.background {
+            Some(background) => {
+                let colors = match *background.get_ref() {
+                    Background::Solid(color) => [color, color],
+                    Background::Gradient { bottom, top } => [bottom, top],
+                };
+                if !colors.iter().all(valid_color) {
+                    return Err(invalid(
+                        Some(background.span()),
+                        "`background` must not be negative".to_string(),
+                    ));
+                }
+                background.into_inner()
+            }
+            None => Background::default(),
+        };
+
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut materials = HashMap::new();
+        for (name, desc) in file.materials {
+            let material = desc
+                .build(dir)
+                .map_err(|(span, message)| invalid(Some(span), message))?;
+            materials.insert(name, material);
+        }
+
+        let mut world = HittableList::default();
+        let mut lights = HittableList::default();
+        let mut meshes = HashMap::new();
+        for desc in file.objects {
+            let object = desc
+                .build(&materials, dir, &mut meshes, Some(&mut lights))
+                .map_err(|(span, message)| invalid(Some(span), message))?;
+            world.add(object);
+        }
+
+        Ok(Self {
+            image: file.image,
+            camera: file.camera,
+            background,
+            world,
+            lights,
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.image.validate()?;
+        self.camera.validate()
+    }
+
+    pub fn camera(&self) -> Camera {
+        Camera::new(
+            self.camera.lookfrom,
+            self.camera.lookat,
+            self.camera.vup,
+            self.camera.vfov,
+            self.image.aspect_ratio,
+            self.camera.aperture,
+            self.camera.focus_dist,
+        )
+        .with_shutter(self.camera.shutter_open, self.camera.shutter_close)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    #[serde(default)]
+    image: ImageSettings,
+    #[serde(default)]
+    camera: CameraSettings,
+    background: Option<Spanned<Background>>,
+    #[serde(default)]
+    materials: HashMap<String, MaterialDesc>,
+    #[serde(default)]
+    objects: Vec<ObjectDesc>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MaterialDesc {
+    #[serde(rename = "type")]
+    kind: Spanned<String>,
+    albedo: Option<Spanned<TextureDesc>>,
+    emit: Option<Spanned<TextureDesc>>,
+    fuzz: Option<Spanned<f64>>,
+    ref_idx: Option<Spanned<f64>>,
+    absorption: Option<Spanned<Pixel>>,
+    cauchy_b: Option<Spanned<f64>>,
+    sellmeier: Option<Spanned<SellmeierDesc>>,
+    roughness: Option<Spanned<f64>>,
+    metallic: Option<Spanned<f64>>,
+    specular: Option<Spanned<f64>>,
+    clearcoat: Option<Spanned<f64>>,
+    clearcoat_roughness: Option<Spanned<f64>>,
+}
+
+impl MaterialDesc {
+    fn build(self, dir: &Path) -> Result<Material, (Range<usize>, String)> {
+        let MaterialDesc {
+            kind,
+            albedo,
+            emit,
+            fuzz,
+            ref_idx,
+            absorption,
+            cauchy_b,
+            sellmeier,
+            roughness,
+            metallic,
+            specular,
+            clearcoat,
+            clearcoat_roughness,
+        } = self;
+        let span = kind.span();
+        let missing = |field: &str| {
+            (
+                span.clone(),
+                format!("`{}` material requires `{}`", kind.get_ref(), field),
+            )
+        };
+        let texture = |desc: Option<Spanned<TextureDesc>>, field: &str| match desc {
+            Some(desc) => {
+                let span = desc.span();
+                desc.into_inner().build(dir).map_err(|e| (span, e))
+            }
+            None => Err(missing(field)),
+        };
+        let has_albedo = albedo.is_some();
+        let albedo = || texture(albedo, "albedo");
+        match kind.get_ref().as_str() {
+            "lambertian" => Ok(Material::Lambertian { albedo: albedo()? }),
+            "metal" => Ok(Material::Metal {
+                albedo: albedo()?,
+                fuzz: fuzz.map_or(Ok(0.0), |f| fraction(f, "fuzz"))?,
+            }),
+            "dielectric" => {
+                let dispersion = match (cauchy_b, sellmeier) {
+                    (Some(_), Some(sellmeier)) => {
+                        return Err((
+                            sellmeier.span(),
+                            "`cauchy_b` and `sellmeier` can't be used together".to_string(),
+                        ))
+                    }
+                    (Some(b), None) => Some(Dispersion::Cauchy {
+                        b: finite_f64(b, "cauchy_b")?,
+                    }),
+                    (None, Some(sellmeier)) => {
+                        let span = sellmeier.span();
+                        let SellmeierDesc { b, c } = sellmeier.into_inner();
+                        if !b.iter().chain(&c).all(|n| n.is_finite()) {
+                            return Err((span, "`sellmeier` must be finite".to_string()));
+                        }
+                        Some(Dispersion::Sellmeier { b, c })
+                    }
+                    (None, None) => None,
+                };
+                // Sellmeier's equation gives the index at the D line itself, so
+                // a second one would disagree with it.
+                let ref_idx = match (ref_idx, dispersion) {
+                    (Some(ref_idx), Some(Dispersion::Sellmeier { .. })) => {
+                        return Err((
+                            ref_idx.span(),
+                            "`ref_idx` and `sellmeier` can't be used together".to_string(),
+                        ))
+                    }
+                    (Some(ref_idx), _) => positive(ref_idx, "ref_idx")?,
+                    (None, Some(sellmeier @ Dispersion::Sellmeier { .. })) => {
+                        sellmeier.ref_idx(0.0, D_LINE)
+                    }
+                    (None, _) => return Err(missing("ref_idx")),
+                };
+                Ok(Material::Dielectric {
+                    ref_idx,
+                    absorption: match absorption {
+                        Some(absorption) => nonnegative(absorption, "absorption")?,
+                        None => Pixel::default(),
+                    },
+                    roughness: roughness.map_or(Ok(0.0), |r| fraction(r, "roughness"))?,
+                    dispersion,
+                })
+            }
+            "isotropic" => Ok(Material::Isotropic { albedo: albedo()? }),
+            "diffuse_light" => Ok(Material::DiffuseLight {
+                emit: texture(emit, "emit")?,
+            }),
+            "principled" => {
+                let defaults = Principled::default();
+                let fraction = |value: Option<Spanned<f64>>, field: &str, default: f64| {
+                    value.map_or(Ok(default), |value| fraction(value, field))
+                };
+                Ok(Material::Principled(Principled {
+                    base_color: if has_albedo {
+                        albedo()?
+                    } else {
+                        defaults.base_color
+                    },
+                    roughness: fraction(roughness, "roughness", defaults.roughness)?,
+                    metallic: fraction(metallic, "metallic", defaults.metallic)?,
+                    specular: fraction(specular, "specular", defaults.specular)?,
+                    clearcoat: fraction(clearcoat, "clearcoat", defaults.clearcoat)?,
+                    clearcoat_roughness: fraction(
+                        clearcoat_roughness,
+                        "clearcoat_roughness",
+                        defaults.clearcoat_roughness,
+                    )?,
+                }))
+            }
+            other => Err((span.clone(), format!("unknown material type `{}`", other))),
+        }
+    }
+}
+
+/// The coefficients of Sellmeier's equation, with `c` in square micrometres.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SellmeierDesc {
+    b: [f64; 3],
+    c: [f64; 3],
+}
+
+/// A material's `albedo`: a colour, or a table describing a texture.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TextureDesc {
+    Color(Pixel),
+    Table(TextureTable),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TextureTable {
+    #[serde(rename = "type")]
+    kind: String,
+    scale: Option<f64>,
+    even: Option<Box<TextureDesc>>,
+    odd: Option<Box<TextureDesc>>,
+    file: Option<String>,
+    wrap: Option<String>,
+}
+
+impl TextureDesc {
+    fn build(self, dir: &Path) -> Result<Texture, String> {
+        let TextureTable {
+            kind,
+            scale,
+            even,
+            odd,
+            file,
+            wrap,
+        } = match self {
+            TextureDesc::Color(color) if valid_color(&color) => return Ok(Texture::Solid(color)),
+            TextureDesc::Color(_) => return Err("colours must not be negative".to_string()),
+            TextureDesc::Table(table) => table,
+        };
+        let missing = |field: &str| format!("`{}` texture requires `{}`", kind, field);
+        if let Some(scale) = scale {
+            if !(scale.is_finite() && scale > 0.0) {
+                return Err("`scale` must be positive".to_string());
+            }
+        }
+        match kind.as_str() {
+            "checker" => Ok(Texture::Checker {
+                scale: scale.unwrap_or(1.0),
+                even: Box::new(even.ok_or_else(|| missing("even"))?.build(dir)?),
+                odd: Box::new(odd.ok_or_else(|| missing("odd"))?.build(dir)?),
+            }),
+            "noise" => Ok(Texture::noise(scale.unwrap_or(1.0))),
+            "image" => {
+                let file = dir.join(file.ok_or_else(|| missing("file"))?);
+                let wrap = wrap.as_deref().unwrap_or("repeat").parse::<Wrap>()?;
+                let image =
+                    ImageTexture::open(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+                Ok(Texture::Image(Arc::new(image.with_wrap(wrap))))
+            }
+            other => Err(format!("unknown texture type `{}`", other)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectDesc {
+    #[serde(rename = "type")]
+    kind: Spanned<String>,
+    material: Option<Spanned<String>>,
+    file: Option<Spanned<String>>,
+    transform: Option<Spanned<TransformDesc>>,
+    operation: Option<Spanned<String>>,
+    left: Option<Box<ObjectDesc>>,
+    right: Option<Box<ObjectDesc>>,
+    boundary: Option<Box<ObjectDesc>>,
+    density: Option<Spanned<f64>>,
+    center: Option<Spanned<Point>>,
+    center0: Option<Spanned<Point>>,
+    center1: Option<Spanned<Point>>,
+    time0: Option<Spanned<f64>>,
+    time1: Option<Spanned<f64>>,
+    point: Option<Spanned<Point>>,
+    normal: Option<Spanned<Point>>,
+    corner: Option<Spanned<Point>>,
+    u: Option<Spanned<Point>>,
+    v: Option<Spanned<Point>>,
+    min: Option<Spanned<Point>>,
+    max: Option<Spanned<Point>>,
+    base: Option<Spanned<Point>>,
+    top: Option<Spanned<Point>>,
+    apex: Option<Spanned<Point>>,
+    axis: Option<Spanned<Point>>,
+    radius: Option<Spanned<f64>>,
+    minor_radius: Option<Spanned<f64>>,
+    capped: Option<bool>,
+    vertices: Option<Spanned<Vec<Point>>>,
+    normals: Option<Spanned<Vec<Point>>>,
+    uvs: Option<Spanned<Vec<[f64; 2]>>>,
+}
+
+type Object = Box<dyn Hittable + Send + Sync>;
+
+/// Meshes loaded so far, keyed by file and default material, so that a file
+/// used by several objects is loaded once and shared.
+type MeshCache = HashMap<(PathBuf, Option<String>), Arc<dyn Hittable + Send + Sync>>;
+
+impl ObjectDesc {
+    fn build(
+        self,
+        materials: &HashMap<String, Material>,
+        dir: &Path,
+        meshes: &mut MeshCache,
+        lights: Option<&mut HittableList>,
+    ) -> Result<Object, (Range<usize>, String)> {
+        let ObjectDesc {
+            kind,
+            material,
+            file,
+            transform,
+            operation,
+            left,
+            right,
+            boundary,
+            density,
+            center,
+            center0,
+            center1,
+            time0,
+            time1,
+            point,
+            normal,
+            corner,
+            u,
+            v,
+            min,
+            max,
+            base,
+            top,
+            apex,
+            axis,
+            radius,
+            minor_radius,
+            capped,
+            vertices,
+            normals,
+            uvs,
+        } = self;
+        let span = kind.span();
+        let missing = |field: &str| {
+            (
+                span.clone(),
+                format!("`{}` requires `{}`", kind.get_ref(), field),
+            )
+        };
+
+        // Lights are only sampled in the world's coordinates.
+        let lights = if transform.is_some() { None } else { lights };
+
+        // A medium's boundary is never seen, so it needn't name a material.
+        let boundary = boundary.map(|mut boundary| {
+            if boundary.material.is_none() {
+                boundary.material = material.clone();
+            }
+            boundary
+        });
+        let material_name = material.as_ref().map(|m| m.get_ref().clone());
+        let material = match material {
+            Some(name) => match materials.get(name.get_ref()) {
+                Some(m) => Some(Box::new(m.clone())),
+                None => {
+                    return Err((
+                        name.span(),
+                        format!("unknown material `{}`", name.get_ref()),
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        let object: Result<Object, _> = match kind.get_ref().as_str() {
+            "sphere" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let center = finite(center.ok_or_else(|| missing("center"))?, "center")?;
+                let radius = positive(radius.ok_or_else(|| missing("radius"))?, "radius")?;
+                let emits = matches!(*material, Material::DiffuseLight { .. });
+                Ok(light(Sphere::new(center, radius, material), emits, lights))
+            }
+            "moving_sphere" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let center0 = finite(center0.ok_or_else(|| missing("center0"))?, "center0")?;
+                let center1 = finite(center1.ok_or_else(|| missing("center1"))?, "center1")?;
+                let time0 = time0.map_or(Ok(0.0), |t| finite_f64(t, "time0"))?;
+                let time1 = time1.map_or(Ok(1.0), |t| finite_f64(t, "time1"))?;
+                if time1 <= time0 {
+                    return Err((span.clone(), "`time1` must be after `time0`".to_string()));
+                }
+                let radius = positive(radius.ok_or_else(|| missing("radius"))?, "radius")?;
+                Ok(Box::new(MovingSphere::new(
+                    (center0, time0),
+                    (center1, time1),
+                    radius,
+                    material,
+                )))
+            }
+            "disk" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let center = finite(center.ok_or_else(|| missing("center"))?, "center")?;
+                let normal = nonzero(normal.ok_or_else(|| missing("normal"))?, "normal")?;
+                let radius = positive(radius.ok_or_else(|| missing("radius"))?, "radius")?;
+                Ok(Box::new(Disk::new(center, normal, radius, material)))
+            }
+            "cylinder" | "cone" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let base = finite(base.ok_or_else(|| missing("base"))?, "base")?;
+                let (end, end_point) = if kind.get_ref() == "cylinder" {
+                    ("top", top)
+                } else {
+                    ("apex", apex)
+                };
+                let end_point = finite(end_point.ok_or_else(|| missing(end))?, end)?;
+                if (end_point - base).length_squared() == 0.0 {
+                    return Err((span.clone(), format!("`base` and `{}` must differ", end)));
+                }
+                let radius = positive(radius.ok_or_else(|| missing("radius"))?, "radius")?;
+                let capped = capped.unwrap_or(true);
+                if kind.get_ref() == "cylinder" {
+                    Ok(Box::new(Cylinder::new(
+                        base, end_point, radius, capped, material,
+                    )))
+                } else {
+                    Ok(Box::new(Cone::new(
+                        base, end_point, radius, capped, material,
+                    )))
+                }
+            }
+            "torus" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let center = finite(center.ok_or_else(|| missing("center"))?, "center")?;
+                let axis = nonzero(axis.ok_or_else(|| missing("axis"))?, "axis")?;
+                let radius = positive(radius.ok_or_else(|| missing("radius"))?, "radius")?;
+                let minor_radius = positive(
+                    minor_radius.ok_or_else(|| missing("minor_radius"))?,
+                    "minor_radius",
+                )?;
+                Ok(Box::new(Torus::new(
+                    center,
+                    axis,
+                    radius,
+                    minor_radius,
+                    material,
+                )))
+            }
+            "triangle" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let vertices = vertices.ok_or_else(|| missing("vertices"))?;
+                let mut triangle = Triangle::new(three_points(vertices, "vertices")?, material);
+                if let Some(normals) = normals {
+                    triangle.normals = Some(three_points(normals, "normals")?);
+                }
+                if let Some(uvs) = uvs {
+                    let span = uvs.span();
+                    let [a, b, c] = three(uvs, "uvs")?;
+                    if ![a, b, c].iter().flatten().all(|n| n.is_finite()) {
+                        return Err((span, "`uvs` must be finite".to_string()));
+                    }
+                    triangle.uvs = Some([(a[0], a[1]), (b[0], b[1]), (c[0], c[1])]);
+                }
+                Ok(Box::new(triangle))
+            }
+            "plane" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let point = finite(point.ok_or_else(|| missing("point"))?, "point")?;
+                let normal = nonzero(normal.ok_or_else(|| missing("normal"))?, "normal")?;
+                Ok(Box::new(Plane::new(point, normal, material)))
+            }
+            "quad" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let corner = finite(corner.ok_or_else(|| missing("corner"))?, "corner")?;
+                let u = u.ok_or_else(|| missing("u"))?;
+                let span = u.span();
+                let u = nonzero(u, "u")?;
+                let v = nonzero(v.ok_or_else(|| missing("v"))?, "v")?;
+                if u.cross(&v).length_squared() == 0.0 {
+                    return Err((span, "`u` and `v` must not be parallel".to_string()));
+                }
+                let emits = matches!(*material, Material::DiffuseLight { .. });
+                Ok(light(Quad::new(corner, u, v, material), emits, lights))
+            }
+            "rect" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let min = finite(min.ok_or_else(|| missing("min"))?, "min")?;
+                let max = max.ok_or_else(|| missing("max"))?;
+                let span = max.span();
+                let max = finite(max, "max")?;
+                let emits = matches!(*material, Material::DiffuseLight { .. });
+                match (min.x == max.x, min.y == max.y, min.z == max.z) {
+                    (true, false, false) => {
+                        let rect = YzRect::new(min.y, max.y, min.z, max.z, min.x, material);
+                        Ok(light(rect, emits, lights))
+                    }
+                    (false, true, false) => {
+                        let rect = XzRect::new(min.x, max.x, min.z, max.z, min.y, material);
+                        Ok(light(rect, emits, lights))
+                    }
+                    (false, false, true) => {
+                        let rect = XyRect::new(min.x, max.x, min.y, max.y, min.z, material);
+                        Ok(light(rect, emits, lights))
+                    }
+                    _ => {
+                        return Err((
+                            span,
+                            "`min` and `max` must be equal on exactly one axis".to_string(),
+                        ))
+                    }
+                }
+            }
+            "cuboid" => {
+                let material = material.ok_or_else(|| missing("material"))?;
+                let min = finite(min.ok_or_else(|| missing("min"))?, "min")?;
+                let max = max.ok_or_else(|| missing("max"))?;
+                let span = max.span();
+                let max = finite(max, "max")?;
+                if min.x == max.x || min.y == max.y || min.z == max.z {
+                    return Err((
+                        span,
+                        "`min` and `max` must differ on every axis".to_string(),
+                    ));
+                }
+                Ok(Box::new(Cuboid::new(min, max, material)))
+            }
+            "obj" => {
+                let file = file.ok_or_else(|| missing("file"))?;
+                // Faces the OBJ file doesn't give a material default to grey.
+                let material = material.map_or(
+                    Material::Lambertian {
+                        albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+                    },
+                    |m| *m,
+                );
+                let path = dir.join(file.get_ref());
+                let key = (path.clone(), material_name);
+                let mesh = match meshes.get(&key) {
+                    Some(mesh) => mesh.clone(),
+                    None => {
+                        let list =
+                            obj::load(&path, material).map_err(|e| (file.span(), e.to_string()))?;
+                        let mesh: Arc<dyn Hittable + Send + Sync> = Arc::new(list);
+                        meshes.insert(key, mesh.clone());
+                        mesh
+                    }
+                };
+                Ok(Box::new(mesh))
+            }
+            "csg" => {
+                let operation = operation.ok_or_else(|| missing("operation"))?;
+                let op = match operation.get_ref().as_str() {
+                    "union" => CsgOp::Union,
+                    "intersection" => CsgOp::Intersection,
+                    "difference" => CsgOp::Difference,
+                    other => {
+                        return Err((
+                            operation.span(),
+                            format!(
+                            "unknown operation `{}`, expected union, intersection or difference",
+                            other
+                        ),
+                        ))
+                    }
+                };
+                let left = left.ok_or_else(|| missing("left"))?;
+                let right = right.ok_or_else(|| missing("right"))?;
+                Ok(Box::new(Csg::new(
+                    op,
+                    left.build(materials, dir, meshes, None)?,
+                    right.build(materials, dir, meshes, None)?,
+                )))
+            }
+            "medium" => {
+                let phase = material.ok_or_else(|| missing("material"))?;
+                let density = positive(density.ok_or_else(|| missing("density"))?, "density")?;
+                let boundary = boundary.ok_or_else(|| missing("boundary"))?;
+                let boundary = boundary.build(materials, dir, meshes, None)?;
+                Ok(Box::new(ConstantMedium::new(boundary, density, phase)))
+            }
+            other => Err((span.clone(), format!("unknown object type `{}`", other))),
+        };
+
+        match transform {
+            Some(transform) => {
+                if !transform.get_ref().is_finite() {
+                    return Err((transform.span(), "transform must be finite".to_string()));
+                }
+                let matrix = transform.get_ref().matrix();
+                if matrix.inverse().is_none() {
+                    return Err((transform.span(), "transform is singular".to_string()));
+                }
+                Ok(Box::new(Transform::new(Arc::from(object?), matrix)))
+            }
+            None => object,
+        }
+    }
+}
+
+/// Box up `object`, also adding it to `lights` to be sampled directly if it
+/// `emits` light.
+fn light<T>(object: T, emits: bool, lights: Option<&mut HittableList>) -> Object
+where
+    T: Hittable + Send + Sync + 'static,
+{
+    match lights {
+        Some(lights) if emits => {
+            let object = Arc::new(object);
+            lights.add(Box::new(object.clone()));
+            Box::new(object)
+        }
+        _ => Box::new(object),
+    }
+}
+
+/// Scaling, then rotation about x, y and z in turn, then translation.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransformDesc {
+    scale: Option<Scale>,
+    /// Angles in degrees.
+    rotate: Option<Point>,
+    translate: Option<Point>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scale {
+    Uniform(f64),
+    Axes(Point),
+}
+
+impl TransformDesc {
+    fn is_finite(&self) -> bool {
+        let scale = match self.scale {
+            Some(Scale::Uniform(s)) => s.is_finite(),
+            Some(Scale::Axes(s)) => s.is_finite(),
+            None => true,
+        };
+        scale
+            && self.rotate.is_none_or(|r| r.is_finite())
+            && self.translate.is_none_or(|t| t.is_finite())
+    }
+
+    fn matrix(&self) -> Mat4 {
+        let mut matrix = match self.scale {
+            Some(Scale::Uniform(s)) => Mat4::scaling(Point::new(s, s, s)),
+            Some(Scale::Axes(s)) => Mat4::scaling(s),
+            None => Mat4::identity(),
+        };
+        if let Some(r) = self.rotate {
+            for (axis, degrees) in [
+                (Point::new(1.0, 0.0, 0.0), r.x),
+                (Point::new(0.0, 1.0, 0.0), r.y),
+                (Point::new(0.0, 0.0, 1.0), r.z),
+            ] {
+                matrix = Mat4::rotation(axis, degrees.to_radians()) * matrix;
+            }
+        }
+        if let Some(t) = self.translate {
+            matrix = Mat4::translation(t) * matrix;
+        }
+        matrix
+    }
+}
+
+fn three<T: Copy>(values: Spanned<Vec<T>>, field: &str) -> Result<[T; 3], (Range<usize>, String)> {
+    match values.get_ref()[..] {
+        [a, b, c] => Ok([a, b, c]),
+        _ => Err((
+            values.span(),
+            format!(
+                "`{}` needs exactly 3 entries, found {}",
+                field,
+                values.get_ref().len()
+            ),
+        )),
+    }
+}
+
+/// Three points, none of them infinite or NaN.
+fn three_points(
+    values: Spanned<Vec<Point>>,
+    field: &str,
+) -> Result<[Point; 3], (Range<usize>, String)> {
+    let span = values.span();
+    let points = three(values, field)?;
+    if !points.iter().all(Point::is_finite) {
+        return Err((span, format!("`{}` must be finite", field)));
+    }
+    Ok(points)
+}
+
+fn finite_f64(value: Spanned<f64>, field: &str) -> Result<f64, (Range<usize>, String)> {
+    if !value.get_ref().is_finite() {
+        return Err((value.span(), format!("`{}` must be finite", field)));
+    }
+    Ok(value.into_inner())
+}
+
+fn positive(value: Spanned<f64>, field: &str) -> Result<f64, (Range<usize>, String)> {
+    let v = *value.get_ref();
+    if !(v.is_finite() && v > 0.0) {
+        return Err((value.span(), format!("`{}` must be positive", field)));
+    }
+    Ok(value.into_inner())
+}
+
+fn fraction(value: Spanned<f64>, field: &str) -> Result<f64, (Range<usize>, String)> {
+    if !(0.0..=1.0).contains(value.get_ref()) {
+        return Err((value.span(), format!("`{}` must be between 0 and 1", field)));
+    }
+    Ok(value.into_inner())
+}
+
+fn nonnegative(color: Spanned<Pixel>, field: &str) -> Result<Pixel, (Range<usize>, String)> {
+    if !valid_color(color.get_ref()) {
+        return Err((color.span(), format!("`{}` must not be negative", field)));
+    }
+    Ok(color.into_inner())
+}
+
+fn nonzero(vector: Spanned<Point>, field: &str) -> Result<Point, (Range<usize>, String)> {
+    let span = vector.span();
+    let vector = finite(vector, field)?;
+    if vector.length_squared() == 0.0 {
+        return Err((span, format!("`{}` must not be zero", field)));
+    }
+    Ok(vector)
+}
+
+fn finite(point: Spanned<Point>, field: &str) -> Result<Point, (Range<usize>, String)> {
+    if !point.get_ref().is_finite() {
+        return Err((point.span(), format!("`{}` must be finite", field)));
+    }
+    Ok(point.into_inner())
+}
+
+/// Whether every channel of `color` is finite and not negative.
+fn valid_color(color: &Pixel) -> bool {
+    let Pixel { r, g, b } = *color;
+    [r, g, b].iter().all(|c| c.is_finite() && *c >= 0.0)
+}
+
+/// Find the 1-based line number of byte `offset` in `source` and the dotted
+/// key that is assigned on that line, qualified by the enclosing table.
+fn locate(source: &str, offset: usize) -> (usize, String) {
+    let offset = offset.min(source.len());
+    let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[offset..]
+        .find('\n')
+        .map_or(source.len(), |i| offset + i);
+    let line = source[..line_start].matches('\n').count() + 1;
+    let text = source[line_start..line_end].trim();
+
+    let table = source[..line_start]
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|l| l.starts_with('['))
+        .map(|l| l.trim_matches(|c| c == '[' || c == ']').trim().to_string());
+
+    let key = if text.starts_with('[') {
+        text.trim_matches(|c| c == '[' || c == ']')
+            .trim()
+            .to_string()
+    } else {
+        let name = text.split('=').next().unwrap_or("").trim();
+        match table {
+            Some(table) => format!("{}.{}", table, name),
+            None => name.to_string(),
+        }
+    };
+    (line, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<Scene, SceneError> {
+        Scene::parse(source, Path::new("test.toml"))
+    }
+
+    /// The line, key and message of the error `source` is rejected with.
+    fn invalid(source: &str) -> (usize, String, String) {
+        match parse(source) {
+            Err(SceneError::Invalid {
+                line, key, message, ..
+            }) => (line, key, message),
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("scene was accepted"),
+        }
+    }
+
+    #[test]
+    fn empty_scene_uses_defaults() {
+        let scene = parse("").unwrap();
+        assert_eq!(scene.image.width, ImageSettings::default().width);
+        assert_eq!(scene.camera.vfov, CameraSettings::default().vfov);
+        assert!(scene.world.objects.is_empty());
+        assert!(scene.lights.objects.is_empty());
+    }
+
+    #[test]
+    fn reads_settings_materials_and_objects() {
+        let scene = parse(
+            r#"
+[image]
+width = 320
+aspect_ratio = 2.0
+tone_map = "aces"
+
+[camera]
+lookfrom = [1.0, 2.0, 3.0]
+
+[materials.matte]
+type = "lambertian"
+albedo = [0.5, 0.5, 0.5]
+
+[materials.lamp]
+type = "diffuse_light"
+emit = [4.0, 4.0, 4.0]
+
+[[objects]]
+type = "sphere"
+center = [0.0, 1.0, 0.0]
+radius = 1.0
+material = "matte"
+
+[[objects]]
+type = "sphere"
+center = [0.0, 5.0, 0.0]
+radius = 0.5
+material = "lamp"
+"#,
+        )
+        .unwrap();
+        assert_eq!(scene.image.width, 320);
+        assert_eq!(scene.image.height(), 160);
+        assert_eq!(scene.image.tone_map, ToneMapper::Aces);
+        let from = scene.camera.lookfrom;
+        assert_eq!((from.x, from.y, from.z), (1.0, 2.0, 3.0));
+        assert_eq!(scene.world.objects.len(), 2);
+        // Only the emissive sphere is sampled as a light.
+        assert_eq!(scene.lights.objects.len(), 1);
+    }
+
+    #[test]
+    fn errors_name_the_line_and_key() {
+        let source = r#"
+[materials.matte]
+type = "lambertian"
+albedo = [0.5, 0.5, 0.5]
+
+[[objects]]
+type = "sphere"
+center = [0.0, 0.0, 0.0]
+radius = -1.0
+material = "matte"
+"#;
+        let (line, key, message) = invalid(source);
+        assert_eq!((line, key.as_str()), (9, "objects.radius"));
+        assert_eq!(message, "`radius` must be positive");
+
+        let error = parse(source).err().unwrap().to_string();
+        assert_eq!(
+            error,
+            "test.toml:9: `objects.radius`: `radius` must be positive"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_materials() {
+        let (line, key, _) = invalid("[image]\nwidth = 10\nheight = 10\n");
+        assert_eq!((line, key.as_str()), (3, "image.height"));
+
+        let (line, key, message) = invalid(
+            "[[objects]]\ntype = \"sphere\"\ncenter = [0.0, 0.0, 0.0]\nradius = 1.0\nmaterial = \"gold\"\n",
+        );
+        assert_eq!((line, key.as_str()), (5, "objects.material"));
+        assert_eq!(message, "unknown material `gold`");
+    }
+
+    #[test]
+    fn rejects_non_finite_values() {
+        let sphere = "[materials.matte]\ntype = \"lambertian\"\nalbedo = [0.5, 0.5, 0.5]\n\n\
+                      [[objects]]\ntype = \"sphere\"\nradius = 1.0\nmaterial = \"matte\"\n";
+        let (line, key, message) = invalid(&format!("{}center = [nan, 0.0, 0.0]\n", sphere));
+        assert_eq!((line, key.as_str()), (9, "objects.center"));
+        assert_eq!(message, "`center` must be finite");
+
+        let (line, key, _) = invalid("background = [-1.0, 0.0, 0.0]\n");
+        assert_eq!((line, key.as_str()), (1, "background"));
+
+        let scene = parse("[camera]\nlookfrom = [inf, 0.0, 0.0]\n").unwrap();
+        assert!(scene.validate().is_err());
+    }
+
+    #[test]
+    fn validation_needs_two_pixels_each_way() {
+        let mut image = ImageSettings {
+            width: 1,
+            ..ImageSettings::default()
+        };
+        assert!(image.validate().is_err());
+        image.width = 400;
+        image.aspect_ratio = 400.0;
+        assert!(image.validate().is_err());
+        image.aspect_ratio = 2.0;
+        assert!(image.validate().is_ok());
+    }
+
+    #[test]
+    fn locate_qualifies_keys_by_table() {
+        let source = "top = 1\n\n[camera]\nvfov = 20.0\n\n[[objects]]\ntype = \"sphere\"\n";
+        assert_eq!(locate(source, 0), (1, "top".to_string()));
+        let vfov = source.find("20.0").unwrap();
+        assert_eq!(locate(source, vfov), (4, "camera.vfov".to_string()));
+        let header = source.find("[[objects]]").unwrap();
+        assert_eq!(locate(source, header), (6, "objects".to_string()));
+        let kind = source.find("\"sphere\"").unwrap();
+        assert_eq!(locate(source, kind), (7, "objects.type".to_string()));
+        // Offsets past the end land on the last line.
+        assert_eq!(locate(source, source.len() + 10).0, 8);
+    }
+}
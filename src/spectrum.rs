@@ -0,0 +1,239 @@
+//! Spectral rendering, where each path carries a single wavelength in
+//! nanometres. RGB colours are turned into spectra by Smits' method from "An
+//! RGB-to-Spectrum Conversion for Reflectances" (1999), and radiance back into
+//! RGB through Wyman, Sloan and Shirley's fit of the CIE 1931 colour matching
+//! functions from "Simple Analytic Approximations to the CIE XYZ Color
+//! Matching Functions" (2013).
+
+use rand::Rng;
+
+use crate::pixel::Pixel;
+
+/// The shortest and longest wavelengths sampled, which Smits' spectra span.
+pub const MIN_WAVELENGTH: f64 = 380.0;
+pub const MAX_WAVELENGTH: f64 = 720.0;
+
+/// The sodium D line, at which refractive indices are usually quoted.
+pub const D_LINE: f64 = 589.3;
+
+/// How a dielectric's refractive index changes with wavelength, splitting
+/// white light into colours.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dispersion {
+    /// Cauchy's equation, n = A + B / λ² with λ in micrometres, where A is
+    /// picked so the index at the D line is the dielectric's own.
+    Cauchy { b: f64 },
+    /// Sellmeier's equation, n² = 1 + Σ Bᵢ λ² / (λ² - Cᵢ) with λ in
+    /// micrometres, which gives the index outright.
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    /// The index at `wavelength` of a material whose index at the D line is
+    /// `ref_idx`.
+    pub fn ref_idx(&self, ref_idx: f64, wavelength: f64) -> f64 {
+        let micrometres = |nm: f64| nm / 1000.0;
+        match self {
+            Dispersion::Cauchy { b } => {
+                let inverse_square = |nm: f64| 1.0 / (micrometres(nm) * micrometres(nm));
+                ref_idx + b * (inverse_square(wavelength) - inverse_square(D_LINE))
+            }
+            Dispersion::Sellmeier { b, c } => {
+                let l2 = micrometres(wavelength) * micrometres(wavelength);
+                let sum: f64 = b.iter().zip(c).map(|(b, c)| b * l2 / (l2 - c)).sum();
+                (1.0 + sum).max(1.0).sqrt()
+            }
+        }
+    }
+}
+
+/// A wavelength for a path, drawn uniformly from the range sampled.
+pub fn random_wavelength() -> f64 {
+    rand::thread_rng().gen_range(MIN_WAVELENGTH, MAX_WAVELENGTH)
+}
+
+/// `color` as seen at `wavelength`: unchanged when rendering in RGB, and
+/// otherwise the value of its spectrum there in every channel.
+pub fn at_wavelength(color: Pixel, wavelength: Option<f64>) -> Pixel {
+    match wavelength {
+        Some(wavelength) => {
+            let value = upsample(color, wavelength);
+            Pixel::new(value, value, value)
+        }
+        None => color,
+    }
+}
+
+/// The linear RGB that `radiance` at a `wavelength` from `random_wavelength`
+/// adds to a pixel. Averaged over wavelengths, a flat spectrum of 1 comes
+/// out as white.
+pub fn to_rgb(radiance: f64, wavelength: f64) -> Pixel {
+    let (x, y, z) = color_matching(wavelength);
+    let scale = radiance * (MAX_WAVELENGTH - MIN_WAVELENGTH);
+    Pixel::new(
+        (3.2404542 * x - 1.5371385 * y - 0.4985314 * z) * scale / WHITE[0],
+        (-0.9692660 * x + 1.8760108 * y + 0.0415560 * z) * scale / WHITE[1],
+        (0.0556434 * x - 0.2040259 * y + 1.0572252 * z) * scale / WHITE[2],
+    )
+}
+
+/// The linear sRGB of a flat spectrum of 1 across the range sampled, which
+/// `to_rgb` divides by to keep it white.
+const WHITE: [f64; 3] = [128.359, 101.528, 97.066];
+
+/// The CIE 1931 x̄, ȳ and z̄ at `wavelength`.
+fn color_matching(wavelength: f64) -> (f64, f64, f64) {
+    // A Gaussian with different widths either side of its peak.
+    let g = |mean: f64, below: f64, above: f64| {
+        let t = (wavelength - mean) / if wavelength < mean { below } else { above };
+        (-0.5 * t * t).exp()
+    };
+    let x =
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2);
+    let y = 0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1);
+    let z = 1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8);
+    (x, y, z)
+}
+
+/// Smits' spectra, in ten equal bins from `MIN_WAVELENGTH` to
+/// `MAX_WAVELENGTH`.
+const WHITE_SPECTRUM: [f64; 10] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const CYAN_SPECTRUM: [f64; 10] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const MAGENTA_SPECTRUM: [f64; 10] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const YELLOW_SPECTRUM: [f64; 10] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const RED_SPECTRUM: [f64; 10] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const GREEN_SPECTRUM: [f64; 10] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const BLUE_SPECTRUM: [f64; 10] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+/// The value at `wavelength` of a smooth spectrum with the colour `color`:
+/// white for the smallest channel, topped up with the secondary and then the
+/// primary colour that make up the rest.
+fn upsample(color: Pixel, wavelength: f64) -> f64 {
+    let t = (wavelength - MIN_WAVELENGTH) / (MAX_WAVELENGTH - MIN_WAVELENGTH);
+    let bin = ((t * 10.0) as usize).min(9);
+    let Pixel { r, g, b } = color;
+    let (amounts, spectra) = if r <= g && r <= b {
+        if g <= b {
+            ((r, g - r, b - g), (CYAN_SPECTRUM, BLUE_SPECTRUM))
+        } else {
+            ((r, b - r, g - b), (CYAN_SPECTRUM, GREEN_SPECTRUM))
+        }
+    } else if g <= r && g <= b {
+        if r <= b {
+            ((g, r - g, b - r), (MAGENTA_SPECTRUM, BLUE_SPECTRUM))
+        } else {
+            ((g, b - g, r - b), (MAGENTA_SPECTRUM, RED_SPECTRUM))
+        }
+    } else if r <= g {
+        ((b, r - b, g - r), (YELLOW_SPECTRUM, GREEN_SPECTRUM))
+    } else {
+        ((b, g - b, r - g), (YELLOW_SPECTRUM, RED_SPECTRUM))
+    };
+    let (white, secondary, primary) = amounts;
+    let (secondary_spectrum, primary_spectrum) = spectra;
+    white * WHITE_SPECTRUM[bin]
+        + secondary * secondary_spectrum[bin]
+        + primary * primary_spectrum[bin]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The RGB a pixel converges to when every wavelength sees `spectrum`,
+    /// integrating `to_rgb` with the midpoint rule.
+    fn rgb_of(spectrum: impl Fn(f64) -> f64) -> Pixel {
+        let n = 3400;
+        let step = (MAX_WAVELENGTH - MIN_WAVELENGTH) / n as f64;
+        let mut sum = Pixel::new(0.0, 0.0, 0.0);
+        for i in 0..n {
+            let wavelength = MIN_WAVELENGTH + (i as f64 + 0.5) * step;
+            sum = sum + to_rgb(spectrum(wavelength), wavelength);
+        }
+        sum * (1.0 / n as f64)
+    }
+
+    fn assert_pixel_close(a: Pixel, b: Pixel, tolerance: f64) {
+        let close = |x: f64, y: f64| (x - y).abs() < tolerance;
+        assert!(
+            close(a.r, b.r) && close(a.g, b.g) && close(a.b, b.b),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn flat_spectrum_is_white() {
+        assert_pixel_close(rgb_of(|_| 1.0), Pixel::new(1.0, 1.0, 1.0), 1e-3);
+        assert_pixel_close(rgb_of(|_| 0.25), Pixel::new(0.25, 0.25, 0.25), 1e-3);
+    }
+
+    #[test]
+    fn white_and_grey_albedos_round_trip() {
+        for &v in &[1.0, 0.5, 0.1] {
+            let grey = Pixel::new(v, v, v);
+            assert_pixel_close(rgb_of(|w| upsample(grey, w)), grey, 1e-3);
+        }
+    }
+
+    #[test]
+    fn colours_keep_their_hue() {
+        let colors = [
+            Pixel::new(0.9, 0.2, 0.1),
+            Pixel::new(0.1, 0.8, 0.3),
+            Pixel::new(0.2, 0.3, 0.9),
+            Pixel::new(0.8, 0.8, 0.2),
+        ];
+        for &color in &colors {
+            // Smits' spectra are smooth, so saturated colours come back a
+            // little desaturated, but near enough.
+            assert_pixel_close(rgb_of(|w| upsample(color, w)), color, 0.1);
+        }
+    }
+
+    #[test]
+    fn rgb_renders_leave_colours_alone() {
+        let color = Pixel::new(0.9, 0.2, 0.1);
+        assert_eq!(at_wavelength(color, None), color);
+        let Pixel { r, g, b } = at_wavelength(color, Some(650.0));
+        assert_eq!((r, r), (g, b));
+    }
+
+    #[test]
+    fn dispersion_matches_the_d_line() {
+        let cauchy = Dispersion::Cauchy { b: 0.004 };
+        assert_eq!(cauchy.ref_idx(1.5, D_LINE), 1.5);
+        assert!(cauchy.ref_idx(1.5, 450.0) > cauchy.ref_idx(1.5, 650.0));
+
+        // Schott N-BK7, whose index at the D line is 1.5168.
+        let bk7 = Dispersion::Sellmeier {
+            b: [1.039_612_12, 0.231_792_344, 1.010_469_45],
+            c: [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+        };
+        assert!((bk7.ref_idx(1.0, D_LINE) - 1.5168).abs() < 1e-4);
+        assert!(bk7.ref_idx(1.0, 450.0) > bk7.ref_idx(1.0, 650.0));
+    }
+
+    #[test]
+    fn wavelengths_stay_in_range() {
+        for _ in 0..1000 {
+            let wavelength = random_wavelength();
+            assert!((MIN_WAVELENGTH..MAX_WAVELENGTH).contains(&wavelength));
+        }
+    }
+}
@@ -1,8 +1,13 @@
+use std::f64::consts::PI;
+
+use crate::aabb::Aabb;
+use crate::frame::Frame;
 use crate::hittable::HitRecord;
 use crate::hittable::Hittable;
 use crate::material::Material;
 use crate::point::Point;
 use crate::ray::Ray;
+use rand::Rng;
 
 pub struct Sphere {
     pub center: Point,
@@ -18,6 +23,17 @@ impl Sphere {
             material,
         }
     }
+
+    /// The cosine of the half angle of the cone the sphere fills as seen from
+    /// `origin`, or `None` from inside the sphere.
+    fn cos_theta_max(&self, origin: &Point) -> Option<f64> {
+        let distance_squared = (self.center - *origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return None;
+        }
+        Some((1.0 - radius_squared / distance_squared).sqrt())
+    }
 }
 
 impl Hittable for Sphere {
@@ -35,9 +51,11 @@ impl Hittable for Sphere {
             if temp > t_min && temp < t_max {
                 hit.t = temp;
                 hit.point = ray.at(hit.t);
-                hit.normal = (hit.point - self.center) / self.radius;
                 let outward_normal = (hit.point - self.center) / self.radius;
                 hit.set_face_normal(ray, outward_normal);
+                let (u, v) = uv(&outward_normal);
+                hit.u = u;
+                hit.v = v;
                 hit.material = self.material.clone();
                 return true;
             }
@@ -46,13 +64,60 @@ impl Hittable for Sphere {
             if temp > t_min && temp < t_max {
                 hit.t = temp;
                 hit.point = ray.at(hit.t);
-                hit.normal = (hit.point - self.center) / self.radius;
                 let outward_normal = (hit.point - self.center) / self.radius;
                 hit.set_face_normal(ray, outward_normal);
+                let (u, v) = uv(&outward_normal);
+                hit.u = u;
+                hit.v = v;
                 hit.material = self.material.clone();
                 return true;
             }
         }
         false
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r = Point::new(self.radius, self.radius, self.radius);
+        Some(Aabb::new(self.center - r, self.center + r))
+    }
+
+    /// Directions are sampled uniformly within the cone the sphere fills as
+    /// seen from `origin`, or over all directions from inside it.
+    fn pdf_value(&self, origin: &Point, direction: &Point) -> f64 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        let mut hit = HitRecord::default();
+        if !self.hit(&ray, 0.001, f64::INFINITY, &mut hit) {
+            return 0.0;
+        }
+        match self.cos_theta_max(origin) {
+            Some(cos_theta_max) => 1.0 / (2.0 * PI * (1.0 - cos_theta_max)),
+            None => 1.0 / (4.0 * PI),
+        }
+    }
+
+    fn random(&self, origin: &Point) -> Point {
+        let cos_theta_max = match self.cos_theta_max(origin) {
+            Some(cos_theta_max) => cos_theta_max,
+            None => return Point::random_unit_vector(),
+        };
+        let mut rng = rand::thread_rng();
+        let cos_theta = 1.0 + rng.gen_range(0.0, 1.0) * (cos_theta_max - 1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen_range(0.0, 1.0);
+        let frame = Frame::new(*origin, self.center - *origin);
+        frame.vector_to_world(Point::new(
+            phi.cos() * sin_theta,
+            cos_theta,
+            phi.sin() * sin_theta,
+        ))
+    }
+}
+
+/// Texture coordinates of a point `p` on the unit sphere: `u` is the angle
+/// around the y axis from x = -1, and `v` the angle from y = -1 to y = 1,
+/// both mapped onto [0, 1].
+pub fn uv(p: &Point) -> (f64, f64) {
+    let theta = (-p.y).acos();
+    let phi = (-p.z).atan2(p.x) + PI;
+    (phi / (2.0 * PI), theta / PI)
 }
@@ -0,0 +1,298 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::perlin::Perlin;
+use crate::pixel::Pixel;
+use crate::point::Point;
+use crate::ppm::PPM;
+use crate::tonemap::srgb_eotf;
+
+/// A colour that varies over a surface, looked up by texture coordinates
+/// and position.
+#[derive(Clone)]
+pub enum Texture {
+    Solid(Pixel),
+    /// A 3D checkerboard of cubes `1 / scale` across.
+    Checker {
+        scale: f64,
+        even: Box<Texture>,
+        odd: Box<Texture>,
+    },
+    /// Marble-like veins of Perlin turbulence, `scale` per unit.
+    Noise {
+        scale: f64,
+        perlin: Arc<Perlin>,
+    },
+    Image(Arc<ImageTexture>),
+}
+
+impl Default for Texture {
+    fn default() -> Self {
+        Texture::Solid(Pixel::default())
+    }
+}
+
+impl From<Pixel> for Texture {
+    fn from(color: Pixel) -> Self {
+        Texture::Solid(color)
+    }
+}
+
+impl Texture {
+    pub fn noise(scale: f64) -> Self {
+        Texture::Noise {
+            scale,
+            perlin: Arc::new(Perlin::new()),
+        }
+    }
+
+    pub fn value(&self, u: f64, v: f64, p: &Point) -> Pixel {
+        match self {
+            Texture::Solid(color) => *color,
+            Texture::Checker { scale, even, odd } => {
+                let cell = (scale * p.x).floor() + (scale * p.y).floor() + (scale * p.z).floor();
+                if cell.rem_euclid(2.0) == 0.0 {
+                    even.value(u, v, p)
+                } else {
+                    odd.value(u, v, p)
+                }
+            }
+            Texture::Noise { scale, perlin } => {
+                let t = 0.5 * (1.0 + (scale * p.z + 10.0 * perlin.turbulence(p, 7)).sin());
+                Pixel::new(t, t, t)
+            }
+            Texture::Image(image) => image.value(u, v),
+        }
+    }
+}
+
+/// How texture coordinates outside [0, 1] are brought back onto an image.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Wrap {
+    /// Tile the image.
+    #[default]
+    Repeat,
+    /// Stretch the edge texels outwards.
+    Clamp,
+}
+
+impl FromStr for Wrap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "repeat" => Ok(Wrap::Repeat),
+            "clamp" => Ok(Wrap::Clamp),
+            _ => Err(format!("unknown wrap `{}`, expected repeat or clamp", s)),
+        }
+    }
+}
+
+impl Wrap {
+    fn index(self, i: i64, len: usize) -> usize {
+        match self {
+            Wrap::Repeat => i.rem_euclid(len as i64) as usize,
+            Wrap::Clamp => i.clamp(0, len as i64 - 1) as usize,
+        }
+    }
+}
+
+/// An image wrapped onto a surface by its texture coordinates, with (0, 0)
+/// at the bottom left.
+pub struct ImageTexture {
+    pub width: usize,
+    pub height: usize,
+    /// Linear colours, top row first.
+    pub pixels: Vec<Pixel>,
+    pub wrap: Wrap,
+}
+
+impl ImageTexture {
+    /// Load any format the `image` crate reads, such as PNG, JPEG or the
+    /// Netpbm family, taking its 8 bit values as sRGB.
+    pub fn open(path: &Path) -> image::ImageResult<Self> {
+        let image = image::open(path)?.into_rgb();
+        Ok(ImageTexture {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            pixels: image
+                .pixels()
+                .map(|p| {
+                    let [r, g, b] = p.0;
+                    let linear = |c: u8| srgb_eotf(c as f64 / 255.0);
+                    Pixel::new(linear(r), linear(g), linear(b))
+                })
+                .collect(),
+            wrap: Wrap::default(),
+        })
+    }
+
+    pub fn with_wrap(self, wrap: Wrap) -> Self {
+        ImageTexture { wrap, ..self }
+    }
+
+    /// The colour at `(u, v)`, interpolated bilinearly between the four
+    /// nearest texel centres.
+    pub fn value(&self, u: f64, v: f64) -> Pixel {
+        if self.pixels.is_empty() {
+            return Pixel::new(0.0, 1.0, 1.0);
+        }
+        let x = u * self.width as f64 - 0.5;
+        let y = (1.0 - v) * self.height as f64 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let texel = |i: i64, j: i64| {
+            let i = self.wrap.index(i, self.width);
+            let j = self.wrap.index(j, self.height);
+            self.pixels[j * self.width + i]
+        };
+        let top = texel(x0, y0) * (1.0 - fx) + texel(x0 + 1, y0) * fx;
+        let bottom = texel(x0, y0 + 1) * (1.0 - fx) + texel(x0 + 1, y0 + 1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+}
+
+impl From<&PPM> for ImageTexture {
+    fn from(image: &PPM) -> Self {
+        ImageTexture {
+            width: image.width,
+            height: image.height,
+            pixels: image
+                .pixels
+                .chunks_exact(3)
+                .map(|c| Pixel::new(c[0], c[1], c[2]))
+                .collect(),
+            wrap: Wrap::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(v: f64) -> Pixel {
+        Pixel::new(v, v, v)
+    }
+
+    #[test]
+    fn solid_ignores_coordinates() {
+        let texture = Texture::from(Pixel::new(0.1, 0.2, 0.3));
+        let p = Point::new(4.0, -2.0, 7.5);
+        assert_eq!(texture.value(0.3, 0.9, &p), Pixel::new(0.1, 0.2, 0.3));
+    }
+
+    #[test]
+    fn checker_alternates_between_cells() {
+        let texture = Texture::Checker {
+            scale: 2.0,
+            even: Box::new(gray(1.0).into()),
+            odd: Box::new(gray(0.0).into()),
+        };
+        let at = |x, y, z| texture.value(0.0, 0.0, &Point::new(x, y, z));
+        // Cells are half a unit across.
+        assert_eq!(at(0.25, 0.25, 0.25), gray(1.0));
+        assert_eq!(at(0.75, 0.25, 0.25), gray(0.0));
+        assert_eq!(at(0.75, 0.75, 0.25), gray(1.0));
+        // The pattern carries on below zero without a doubled row.
+        assert_eq!(at(-0.25, 0.25, 0.25), gray(0.0));
+        assert_eq!(at(-0.75, 0.25, 0.25), gray(1.0));
+    }
+
+    #[test]
+    fn noise_stays_in_range() {
+        let texture = Texture::noise(4.0);
+        for i in 0..200 {
+            let p = Point::new(i as f64 * 0.37, i as f64 * -0.11, i as f64 * 0.05);
+            let Pixel { r, g, b } = texture.value(0.0, 0.0, &p);
+            assert!((0.0..=1.0).contains(&r), "{}", r);
+            assert_eq!((r, r), (g, b));
+        }
+    }
+
+    fn image(width: usize, height: usize, pixels: Vec<Pixel>) -> ImageTexture {
+        ImageTexture {
+            width,
+            height,
+            pixels,
+            wrap: Wrap::Repeat,
+        }
+    }
+
+    fn assert_pixel_close(a: Pixel, b: Pixel) {
+        let close = |x: f64, y: f64| (x - y).abs() < 1e-9;
+        assert!(
+            close(a.r, b.r) && close(a.g, b.g) && close(a.b, b.b),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn image_interpolates_between_texel_centres() {
+        let texture = image(2, 1, vec![gray(0.0), gray(1.0)]);
+        assert_pixel_close(texture.value(0.25, 0.5), gray(0.0));
+        assert_pixel_close(texture.value(0.75, 0.5), gray(1.0));
+        assert_pixel_close(texture.value(0.5, 0.5), gray(0.5));
+        assert_pixel_close(texture.value(0.375, 0.5), gray(0.25));
+    }
+
+    #[test]
+    fn image_rows_run_from_the_top() {
+        let red = Pixel::new(1.0, 0.0, 0.0);
+        let blue = Pixel::new(0.0, 0.0, 1.0);
+        let texture = image(1, 2, vec![red, blue]);
+        assert_pixel_close(texture.value(0.5, 0.75), red);
+        assert_pixel_close(texture.value(0.5, 0.25), blue);
+    }
+
+    #[test]
+    fn wrap_modes() {
+        let repeat = image(2, 1, vec![gray(0.0), gray(1.0)]);
+        // Left of the first texel centre blends with the last texel.
+        assert_pixel_close(repeat.value(0.0, 0.5), gray(0.5));
+        assert_pixel_close(repeat.value(1.25, 0.5), gray(0.0));
+        assert_pixel_close(repeat.value(-0.25, 0.5), gray(1.0));
+
+        let clamp = repeat.with_wrap(Wrap::Clamp);
+        assert_pixel_close(clamp.value(0.0, 0.5), gray(0.0));
+        assert_pixel_close(clamp.value(1.25, 0.5), gray(1.0));
+        assert_pixel_close(clamp.value(-3.0, 0.5), gray(0.0));
+
+        assert_eq!("Clamp".parse::<Wrap>(), Ok(Wrap::Clamp));
+        assert!("mirror".parse::<Wrap>().is_err());
+    }
+
+    #[test]
+    fn empty_image_is_cyan() {
+        let texture = image(0, 0, Vec::new());
+        assert_eq!(texture.value(0.5, 0.5), Pixel::new(0.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn open_decodes_srgb() {
+        let path = std::env::temp_dir().join(format!("texture-{}.png", std::process::id()));
+        image::save_buffer(
+            &path,
+            &[0, 0, 0, 188, 255, 10],
+            2,
+            1,
+            image::ColorType::Rgb8,
+        )
+        .unwrap();
+        let texture = ImageTexture::open(&path);
+        std::fs::remove_file(&path).unwrap();
+        let texture = texture.unwrap();
+
+        assert_eq!((texture.width, texture.height), (2, 1));
+        assert_eq!(texture.pixels[0], gray(0.0));
+        let linear = |c: u8| srgb_eotf(c as f64 / 255.0);
+        assert_eq!(texture.pixels[1], Pixel::new(linear(188), 1.0, linear(10)));
+        // sRGB 188 is close to half intensity.
+        assert!((texture.pixels[1].r - 0.5).abs() < 0.01);
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// Curves that map linear radiance onto the displayable range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToneMapper {
+    /// Square root (gamma 2.0) then clamp, as in the book.
+    #[default]
+    Gamma2,
+    /// Clamp without any transfer curve.
+    Clamp,
+    /// Clamp then apply the sRGB transfer function.
+    Srgb,
+    /// Reinhard's x / (1 + x), then sRGB.
+    Reinhard,
+    /// Krzysztof Narkowicz's fit of the ACES filmic curve, then sRGB.
+    Aces,
+}
+
+impl ToneMapper {
+    pub const NAMES: [&'static str; 5] = ["gamma2", "clamp", "srgb", "reinhard", "aces"];
+
+    /// Map a linear channel value to a display value in [0, 1].
+    pub fn apply(self, x: f64) -> f64 {
+        let x = x.max(0.0);
+        match self {
+            ToneMapper::Gamma2 => x.sqrt().min(1.0),
+            ToneMapper::Clamp => x.min(1.0),
+            ToneMapper::Srgb => srgb_oetf(x.min(1.0)),
+            ToneMapper::Reinhard => srgb_oetf(x / (1.0 + x)),
+            ToneMapper::Aces => {
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                let y = (x * (a * x + b)) / (x * (c * x + d) + e);
+                srgb_oetf(y.clamp(0.0, 1.0))
+            }
+        }
+    }
+}
+
+impl FromStr for ToneMapper {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "gamma2" => Ok(ToneMapper::Gamma2),
+            "clamp" => Ok(ToneMapper::Clamp),
+            "srgb" => Ok(ToneMapper::Srgb),
+            "reinhard" => Ok(ToneMapper::Reinhard),
+            "aces" => Ok(ToneMapper::Aces),
+            _ => Err(format!(
+                "unknown tone mapper `{}`, expected one of {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ToneMapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ToneMapper::Gamma2 => "gamma2",
+            ToneMapper::Clamp => "clamp",
+            ToneMapper::Srgb => "srgb",
+            ToneMapper::Reinhard => "reinhard",
+            ToneMapper::Aces => "aces",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The transform from the renderer's linear values to display values: an
+/// exposure adjustment in stops followed by a tone mapping curve.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ToneMap {
+    pub tone_mapper: ToneMapper,
+    pub exposure: f64,
+}
+
+impl ToneMap {
+    pub fn new(tone_mapper: ToneMapper, exposure: f64) -> Self {
+        Self {
+            tone_mapper,
+            exposure,
+        }
+    }
+
+    pub fn apply(&self, x: f64) -> f64 {
+        self.tone_mapper.apply(x * 2_f64.powf(self.exposure))
+    }
+}
+
+/// The sRGB opto-electronic transfer function, for linear `x` in [0, 1].
+pub fn srgb_oetf(x: f64) -> f64 {
+    if x <= 0.003_130_8 {
+        12.92 * x
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The inverse of `srgb_oetf`, decoding an sRGB value in [0, 1] to linear.
+pub fn srgb_eotf(x: f64) -> f64 {
+    if x <= 0.040_45 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [ToneMapper; 5] = [
+        ToneMapper::Gamma2,
+        ToneMapper::Clamp,
+        ToneMapper::Srgb,
+        ToneMapper::Reinhard,
+        ToneMapper::Aces,
+    ];
+
+    #[test]
+    fn curves_stay_in_range_and_increase() {
+        for mapper in ALL {
+            assert_eq!(mapper.apply(0.0), 0.0, "{}", mapper);
+            assert_eq!(mapper.apply(-1.0), 0.0, "{}", mapper);
+            let mut last = 0.0;
+            for i in 1..=200 {
+                let y = mapper.apply(i as f64 * 0.05);
+                assert!(y >= last && y <= 1.0, "{} at {}", mapper, i);
+                last = y;
+            }
+        }
+    }
+
+    #[test]
+    fn known_values() {
+        assert_eq!(ToneMapper::Gamma2.apply(0.25), 0.5);
+        assert_eq!(ToneMapper::Gamma2.apply(4.0), 1.0);
+        assert_eq!(ToneMapper::Clamp.apply(0.25), 0.25);
+        assert!((ToneMapper::Srgb.apply(1.0) - 1.0).abs() < 1e-12);
+        // Reinhard maps 1 to 0.5 before the transfer curve.
+        assert_eq!(ToneMapper::Reinhard.apply(1.0), srgb_oetf(0.5));
+        // Reinhard only approaches 1, while ACES saturates.
+        assert!(ToneMapper::Reinhard.apply(4.0) < 1.0);
+        assert!(ToneMapper::Aces.apply(100.0) > 0.999);
+    }
+
+    #[test]
+    fn srgb_curves_invert_each_other() {
+        for i in 0..=100 {
+            let x = i as f64 / 100.0;
+            assert!((srgb_eotf(srgb_oetf(x)) - x).abs() < 1e-12);
+        }
+        // The linear segment and the power curve meet without a jump.
+        let knee = 0.003_130_8;
+        assert!((srgb_oetf(knee) - srgb_oetf(knee + 1e-9)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn exposure_is_in_stops() {
+        let map = ToneMap::new(ToneMapper::Clamp, 1.0);
+        assert_eq!(map.apply(0.25), 0.5);
+        let map = ToneMap::new(ToneMapper::Clamp, -2.0);
+        assert_eq!(map.apply(0.8), 0.2);
+    }
+
+    #[test]
+    fn names_parse_and_display() {
+        for (mapper, name) in ALL.iter().zip(ToneMapper::NAMES.iter()) {
+            assert_eq!(mapper.to_string(), *name);
+            assert_eq!(name.parse::<ToneMapper>(), Ok(*mapper));
+        }
+        assert_eq!("ACES".parse::<ToneMapper>(), Ok(ToneMapper::Aces));
+        assert!("filmic".parse::<ToneMapper>().is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use std::f64::consts::PI;
+
+use crate::aabb::Aabb;
+use crate::disk;
+use crate::frame::{self, Frame};
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// A ring around `center`, with its hole along `axis`. The tube's centre is
+/// `major_radius` from the axis and the tube is `minor_radius` thick.
+pub struct Torus {
+    pub center: Point,
+    pub axis: Point,
+    pub major_radius: f64,
+    pub minor_radius: f64,
+    pub material: Box<Material>,
+    frame: Frame,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point,
+        axis: Point,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Box<Material>,
+    ) -> Self {
+        Torus {
+            center,
+            axis: axis.unit_vector(),
+            major_radius,
+            minor_radius,
+            material,
+            frame: Frame::new(center, axis),
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        let (big_r, r) = (self.major_radius, self.minor_radius);
+        let local = self.frame.ray_to_local(ray);
+
+        // Solve with a unit direction, starting from where the ray enters the
+        // bounding sphere, to keep the quartic well conditioned.
+        let scale = local.direction.length();
+        let d = local.direction / scale;
+        let bound = big_r + r;
+        let oc = local.origin.dot(&d);
+        if local.origin.length_squared() - oc * oc > bound * bound {
+            return false;
+        }
+        let start = (-oc - bound).max(0.0);
+        let o = local.origin + d * start;
+
+        // (|p|² + R² - r²)² = 4R²(x² + z²) along p = o + sd.
+        let h = 2.0 * o.dot(&d);
+        let i = o.length_squared() + big_r * big_r - r * r;
+        let four_r2 = 4.0 * big_r * big_r;
+        let roots = solve_quartic(
+            2.0 * h,
+            h * h + 2.0 * i - four_r2 * (d.x * d.x + d.z * d.z),
+            2.0 * h * i - 2.0 * four_r2 * (o.x * d.x + o.z * d.z),
+            i * i - four_r2 * (o.x * o.x + o.z * o.z),
+        );
+
+        let t = roots
+            .into_iter()
+            .map(|s| (s + start) / scale)
+            .filter(|&t| t > t_min && t < t_max)
+            .fold(None, |best: Option<f64>, t| {
+                Some(best.map_or(t, |b| b.min(t)))
+            });
+        let t = match t {
+            Some(t) => t,
+            None => return false,
+        };
+
+        let p = local.at(t);
+        let ring = Point::new(p.x, 0.0, p.z).unit_vector() * big_r;
+        let normal = (p - ring) / r;
+        let around = p.y.atan2(Point::new(p.x, 0.0, p.z).length() - big_r);
+        let uv = (frame::azimuth(p), (around + PI) / (2.0 * PI));
+        frame::set_hit(hit, ray, &self.frame, t, normal, uv);
+        hit.material = self.material.clone();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let ring = disk::bounding_box(self.center, self.axis, self.major_radius);
+        let r = Point::new(self.minor_radius, self.minor_radius, self.minor_radius);
+        Some(Aabb::new(ring.min - r, ring.max + r))
+    }
+}
+
+/// The real roots of x⁴ + ax³ + bx² + cx + d by Ferrari's method, each
+/// refined with a few Newton steps.
+fn solve_quartic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    // Depress to y⁴ + py² + qy + r with x = y - a/4.
+    let a2 = a * a;
+    let p = b - 3.0 * a2 / 8.0;
+    let q = c - a * b / 2.0 + a2 * a / 8.0;
+    let r = d - a * c / 4.0 + a2 * b / 16.0 - 3.0 * a2 * a2 / 256.0;
+
+    let mut ys = Vec::with_capacity(4);
+    if q.abs() < 1e-12 {
+        // Biquadratic: solve for y².
+        for z in solve_quadratic(p, r) {
+            if z >= 0.0 {
+                ys.push(z.sqrt());
+                ys.push(-z.sqrt());
+            }
+        }
+    } else {
+        // Write the quartic as (y² + m)² = (2m - p)y² - qy + m² - r and pick
+        // m from the resolvent cubic so the right side is a perfect square.
+        let m = solve_cubic(-p / 2.0, -r, (4.0 * p * r - q * q) / 8.0);
+        let s = (2.0 * m - p).max(0.0).sqrt();
+        if s == 0.0 {
+            return Vec::new();
+        }
+        ys.extend(solve_quadratic(-s, m + q / (2.0 * s)));
+        ys.extend(solve_quadratic(s, m - q / (2.0 * s)));
+    }
+
+    ys.into_iter()
+        .map(|y| {
+            let mut x = y - a / 4.0;
+            for _ in 0..3 {
+                let f = (((x + a) * x + b) * x + c) * x + d;
+                let df = ((4.0 * x + 3.0 * a) * x + 2.0 * b) * x + c;
+                if df == 0.0 {
+                    break;
+                }
+                x -= f / df;
+            }
+            x
+        })
+        .collect()
+}
+
+/// The real roots of x² + bx + c.
+fn solve_quadratic(b: f64, c: f64) -> Vec<f64> {
+    let discriminant = b * b / 4.0 - c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+    let root = discriminant.sqrt();
+    vec![-b / 2.0 - root, -b / 2.0 + root]
+}
+
+/// The largest real root of x³ + ax² + bx + c.
+fn solve_cubic(a: f64, b: f64, c: f64) -> f64 {
+    // Depress to t³ + pt + q with x = t - a/3.
+    let p = b - a * a / 3.0;
+    let q = 2.0 * a * a * a / 27.0 - a * b / 3.0 + c;
+    let discriminant = q * q / 4.0 + p * p * p / 27.0;
+    let t = if discriminant > 0.0 {
+        let root = discriminant.sqrt();
+        (-q / 2.0 + root).cbrt() + (-q / 2.0 - root).cbrt()
+    } else if p == 0.0 {
+        0.0
+    } else {
+        // Three real roots; the trigonometric form's first is the largest.
+        let phi = (3.0 * q / (2.0 * p) * (-3.0 / p).sqrt())
+            .clamp(-1.0, 1.0)
+            .acos();
+        2.0 * (-p / 3.0).sqrt() * (phi / 3.0).cos()
+    };
+    t - a / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::Pixel;
+
+    fn torus(axis: Point) -> Torus {
+        let material = Box::new(Material::Lambertian {
+            albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+        });
+        Torus::new(Point::new(0.0, 0.0, 0.0), axis, 2.0, 0.5, material)
+    }
+
+    fn trace(torus: &Torus, origin: Point, direction: Point) -> Option<HitRecord> {
+        let mut hit = HitRecord::default();
+        let ray = Ray::new(origin, direction, 0.0);
+        torus
+            .hit(&ray, 0.001, f64::INFINITY, &mut hit)
+            .then_some(hit)
+    }
+
+    fn assert_roots(mut roots: Vec<f64>, expected: &[f64]) {
+        roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(roots.len(), expected.len(), "{:?}", roots);
+        for (root, expected) in roots.iter().zip(expected) {
+            assert!(
+                (root - expected).abs() < 1e-9,
+                "{:?} != {:?}",
+                roots,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn biquadratic_roots() {
+        // (x² - 1)(x² - 4)
+        assert_roots(solve_quartic(0.0, -5.0, 0.0, 4.0), &[-2.0, -1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn general_quartic_roots() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        assert_roots(
+            solve_quartic(-10.0, 35.0, -50.0, 24.0),
+            &[1.0, 2.0, 3.0, 4.0],
+        );
+        // (x² + 1)(x² + 2) has no real roots.
+        assert_roots(solve_quartic(0.0, 3.0, 0.0, 2.0), &[]);
+    }
+
+    #[test]
+    fn largest_cubic_root() {
+        // (x - 1)(x - 2)(x - 3), with three real roots.
+        assert!((solve_cubic(-6.0, 11.0, -6.0) - 3.0).abs() < 1e-9);
+        // x³ - 8, with one.
+        assert!((solve_cubic(0.0, 0.0, -8.0) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tube_hit() {
+        let t = torus(Point::new(0.0, 1.0, 0.0));
+        let hit = trace(&t, Point::new(10.0, 0.0, 0.0), Point::new(-1.0, 0.0, 0.0)).unwrap();
+        assert!((hit.t - 7.5).abs() < 1e-9);
+        assert!((hit.normal - Point::new(1.0, 0.0, 0.0)).length() < 1e-9);
+    }
+
+    #[test]
+    fn ray_through_the_hole_misses() {
+        let t = torus(Point::new(0.0, 1.0, 0.0));
+        assert!(trace(&t, Point::new(0.0, 10.0, 0.0), Point::new(0.0, -1.0, 0.0)).is_none());
+        // Just inside the tube's inner edge, at 1.5 from the axis.
+        assert!(trace(&t, Point::new(1.45, 10.0, 0.0), Point::new(0.0, -1.0, 0.0)).is_none());
+        let hit = trace(&t, Point::new(1.55, 10.0, 0.0), Point::new(0.0, -1.0, 0.0)).unwrap();
+        assert!(hit.t > 9.5 && hit.t < 10.0);
+    }
+
+    #[test]
+    fn tilted_axis() {
+        let axis = Point::new(1.0, 1.0, 0.0).unit_vector();
+        let t = torus(axis);
+        assert!(trace(&t, axis * 10.0, -axis).is_none());
+        // Along the ring's plane, the outer edge of the tube is 2.5 away.
+        let hit = trace(&t, Point::new(0.0, 0.0, -10.0), Point::new(0.0, 0.0, 1.0)).unwrap();
+        assert!((hit.t - 7.5).abs() < 1e-9);
+        assert!((hit.normal - Point::new(0.0, 0.0, -1.0)).length() < 1e-9);
+    }
+}
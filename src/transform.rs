@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::mat4::Mat4;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// An instance of `object` placed in the world by an affine `matrix`. The
+/// object is shared, so many instances of one mesh cost little memory.
+pub struct Transform {
+    pub object: Arc<dyn Hittable + Send + Sync>,
+    pub matrix: Mat4,
+    inverse: Mat4,
+    /// The inverse transpose, which carries normals out of object space.
+    normal_matrix: Mat4,
+}
+
+impl Transform {
+    /// Panics if `matrix` is singular.
+    pub fn new(object: Arc<dyn Hittable + Send + Sync>, matrix: Mat4) -> Self {
+        let inverse = matrix.inverse().expect("transform matrix is singular");
+        Transform {
+            object,
+            matrix,
+            inverse,
+            normal_matrix: inverse.transpose(),
+        }
+    }
+}
+
+impl Hittable for Transform {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        // The direction isn't renormalised, so ray parameters carry over.
+        let local = Ray::new(
+            self.inverse.transform_point(ray.origin),
+            self.inverse.transform_vector(ray.direction),
+            ray.time,
+        );
+        if !self.object.hit(&local, t_min, t_max, hit) {
+            return false;
+        }
+        hit.point = self.matrix.transform_point(hit.point);
+        // This keeps the normal's side relative to the ray, so `front_face`
+        // stays valid.
+        hit.normal = self
+            .normal_matrix
+            .transform_vector(hit.normal)
+            .unit_vector();
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let b = self.object.bounding_box()?;
+        let mut corners = (0..8).map(|i| {
+            let corner = Point::new(
+                if i & 1 == 0 { b.min.x } else { b.max.x },
+                if i & 2 == 0 { b.min.y } else { b.max.y },
+                if i & 4 == 0 { b.min.z } else { b.max.z },
+            );
+            let p = self.matrix.transform_point(corner);
+            Aabb::new(p, p)
+        });
+        let first = corners.next()?;
+        Some(corners.fold(first, |acc, c| acc.union(&c)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::material::Material;
+    use crate::pixel::Pixel;
+    use crate::sphere::Sphere;
+
+    fn unit_sphere() -> Arc<dyn Hittable + Send + Sync> {
+        let material = Material::Lambertian {
+            albedo: Pixel::new(0.5, 0.5, 0.5).into(),
+        };
+        Arc::new(Sphere::new(
+            Point::new(0.0, 0.0, 0.0),
+            1.0,
+            Box::new(material),
+        ))
+    }
+
+    fn trace(object: &dyn Hittable, origin: Point, direction: Point) -> Option<HitRecord> {
+        let mut hit = HitRecord::default();
+        object
+            .hit(
+                &Ray::new(origin, direction, 0.0),
+                0.001,
+                f64::INFINITY,
+                &mut hit,
+            )
+            .then_some(hit)
+    }
+
+    fn assert_point_close(a: Point, b: Point) {
+        assert!((a - b).length() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn translation_moves_hits() {
+        let moved = Transform::new(unit_sphere(), Mat4::translation(Point::new(0.0, 0.0, -5.0)));
+        let hit = trace(
+            &moved,
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(0.0, 0.0, -1.0),
+        )
+        .unwrap();
+        assert!((hit.t - 4.0).abs() < 1e-9);
+        assert_point_close(hit.point, Point::new(0.0, 0.0, -4.0));
+        assert_point_close(hit.normal, Point::new(0.0, 0.0, 1.0));
+        assert!(hit.front_face);
+        assert!(trace(
+            &moved,
+            Point::new(2.0, 0.0, 0.0),
+            Point::new(0.0, 0.0, -1.0)
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn scaling_keeps_normals_perpendicular() {
+        // The ellipsoid x^2 / 4 + y^2 + z^2 = 1.
+        let stretched = Transform::new(unit_sphere(), Mat4::scaling(Point::new(2.0, 1.0, 1.0)));
+        let origin = Point::new(1.0, 5.0, 0.0);
+        let hit = trace(&stretched, origin, Point::new(0.0, -1.0, 0.0)).unwrap();
+        let y = (0.75_f64).sqrt();
+        assert_point_close(hit.point, Point::new(1.0, y, 0.0));
+        // The gradient of the implicit surface, (x / 4, y, z).
+        assert_point_close(hit.normal, Point::new(0.25, y, 0.0).unit_vector());
+        assert!((hit.t - (5.0 - y)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hits_from_inside_face_back() {
+        let scaled = Transform::new(unit_sphere(), Mat4::scaling(Point::new(3.0, 3.0, 3.0)));
+        let hit = trace(
+            &scaled,
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+        )
+        .unwrap();
+        assert!(!hit.front_face);
+        assert_point_close(hit.point, Point::new(3.0, 0.0, 0.0));
+        assert_point_close(hit.normal, Point::new(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn bounding_box_covers_rotated_corners() {
+        let matrix = Mat4::translation(Point::new(10.0, 0.0, 0.0))
+            * Mat4::rotation(Point::new(0.0, 0.0, 1.0), std::f64::consts::FRAC_PI_4)
+            * Mat4::scaling(Point::new(2.0, 1.0, 1.0));
+        let bbox = Transform::new(unit_sphere(), matrix)
+            .bounding_box()
+            .unwrap();
+        // The 4 x 2 box turned 45 degrees spans 3 / sqrt(2) either side.
+        let half = 3.0 / 2_f64.sqrt();
+        assert_point_close(bbox.min, Point::new(10.0 - half, -half, -1.0));
+        assert_point_close(bbox.max, Point::new(10.0 + half, half, 1.0));
+    }
+}
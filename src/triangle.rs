@@ -0,0 +1,129 @@
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::point::Point;
+use crate::ray::Ray;
+
+pub struct Triangle {
+    pub vertices: [Point; 3],
+    /// Per-vertex shading normals, interpolated across the face.
+    pub normals: Option<[Point; 3]>,
+    /// Per-vertex texture coordinates.
+    pub uvs: Option<[(f64, f64); 3]>,
+    pub material: Box<Material>,
+}
+
+impl Triangle {
+    pub fn new(vertices: [Point; 3], material: Box<Material>) -> Self {
+        Triangle {
+            vertices,
+            normals: None,
+            uvs: None,
+            material,
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        match intersect(&self.vertices, ray, t_min, t_max) {
+            Some((t, b1, b2)) => {
+                set_hit(
+                    hit,
+                    ray,
+                    t,
+                    (b1, b2),
+                    &self.vertices,
+                    self.normals.as_ref(),
+                    self.uvs.as_ref(),
+                );
+                hit.material = self.material.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(bounding_box(&self.vertices))
+    }
+}
+
+/// Möller–Trumbore ray/triangle intersection. Returns the ray parameter and
+/// the barycentric coordinates of the hit relative to the second and third
+/// vertices.
+pub fn intersect(v: &[Point; 3], ray: &Ray, t_min: f64, t_max: f64) -> Option<(f64, f64, f64)> {
+    let edge1 = v[1] - v[0];
+    let edge2 = v[2] - v[0];
+    let pvec = ray.direction.cross(&edge2);
+    let det = edge1.dot(&pvec);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let tvec = ray.origin - v[0];
+    let b1 = tvec.dot(&pvec) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let qvec = tvec.cross(&edge1);
+    let b2 = ray.direction.dot(&qvec) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(&qvec) * inv_det;
+    if t <= t_min || t >= t_max {
+        return None;
+    }
+    Some((t, b1, b2))
+}
+
+/// Fill in everything but the material for a hit at barycentric `(b1, b2)`.
+/// Which side was hit is decided by the geometric normal, so interpolated
+/// normals only change the shading.
+pub fn set_hit(
+    hit: &mut HitRecord,
+    ray: &Ray,
+    t: f64,
+    (b1, b2): (f64, f64),
+    vertices: &[Point; 3],
+    normals: Option<&[Point; 3]>,
+    uvs: Option<&[(f64, f64); 3]>,
+) {
+    let b0 = 1.0 - b1 - b2;
+    hit.t = t;
+    hit.point = ray.at(t);
+
+    let geometric = (vertices[1] - vertices[0])
+        .cross(&(vertices[2] - vertices[0]))
+        .unit_vector();
+    hit.set_face_normal(ray, geometric);
+    if let Some(n) = normals {
+        let shading = (n[0] * b0 + n[1] * b1 + n[2] * b2).unit_vector();
+        hit.normal = if shading.dot(&hit.normal) < 0.0 {
+            -shading
+        } else {
+            shading
+        };
+    }
+
+    let (u, v) = match uvs {
+        Some(uv) => (
+            uv[0].0 * b0 + uv[1].0 * b1 + uv[2].0 * b2,
+            uv[0].1 * b0 + uv[1].1 * b1 + uv[2].1 * b2,
+        ),
+        None => (b1, b2),
+    };
+    hit.u = u;
+    hit.v = v;
+}
+
+pub fn bounding_box(v: &[Point; 3]) -> Aabb {
+    Aabb::from_points(v[0], v[1])
+        .union(&Aabb::from_points(v[2], v[2]))
+        .padded(1e-4)
+}